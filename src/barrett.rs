@@ -1,11 +1,57 @@
 use super::integer_au::IntegerAU;
+use super::op_count;
+use super::reducer::Reducer;
+use super::reducer::Scratch;
 
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "BarrettParams")
+)]
 pub struct Barrett {
     prime: IntegerAU,
     prime_bit_length: usize,
     barrett_mu: IntegerAU,
 }
 
+// Deserialized parameters, checked before they become a Barrett
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct BarrettParams {
+    prime: IntegerAU,
+    prime_bit_length: usize,
+    barrett_mu: IntegerAU,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<BarrettParams> for Barrett {
+    type Error = String;
+
+    fn try_from(params: BarrettParams) -> Result<Self, String> {
+        let BarrettParams {
+            prime,
+            prime_bit_length,
+            barrett_mu,
+        } = params;
+        if prime < IntegerAU::from(2) || prime_bit_length != prime.bit_len() {
+            return Err(format!(
+                "invalid Barrett modulus {prime} of {prime_bit_length} bits"
+            ));
+        }
+        // mu = floor(2^2k / p) iff 0 <= 2^2k - mu p < p
+        let target = &IntegerAU::from(1) << (2 * prime_bit_length);
+        let product = &barrett_mu * &prime;
+        if product > target || &target - &product >= prime {
+            return Err(format!("invalid Barrett mu for modulus {prime}"));
+        }
+        Ok(Self {
+            prime,
+            prime_bit_length,
+            barrett_mu,
+        })
+    }
+}
+
 impl Barrett {
     pub fn new(prime: IntegerAU) -> Self {
         let barrett_mu = &(IntegerAU::from(1u64) << (2 * prime.bit_len())) / &prime;
@@ -17,6 +63,7 @@ impl Barrett {
     }
 
     pub fn reduce(&self, x: &IntegerAU) -> IntegerAU {
+        op_count::record_reduction();
         let q = &(&(x >> self.prime_bit_length) * &self.barrett_mu) >> self.prime_bit_length;
         let mut r = x - &(&q * &self.prime);
         while r >= self.prime {
@@ -24,4 +71,123 @@ impl Barrett {
         }
         r
     }
+
+    /// Same as `reduce`, writing the result into `out` and keeping the
+    /// intermediates in `scratch` instead of allocating them
+    pub fn reduce_into(&self, x: &IntegerAU, out: &mut IntegerAU, scratch: &mut Scratch) {
+        self.reduce_with(x, out, &mut scratch.a, &mut scratch.b);
+    }
+
+    fn reduce_with(
+        &self,
+        x: &IntegerAU,
+        out: &mut IntegerAU,
+        q: &mut IntegerAU,
+        t: &mut IntegerAU,
+    ) {
+        op_count::record_reduction();
+        q.clone_from(x);
+        *q >>= self.prime_bit_length;
+        IntegerAU::mul_into(q, &self.barrett_mu, t);
+        *t >>= self.prime_bit_length;
+        IntegerAU::mul_into(t, &self.prime, q);
+        out.clone_from(x);
+        *out -= &*q;
+        while *out >= self.prime {
+            *out -= &self.prime;
+        }
+    }
+}
+
+impl Reducer for Barrett {
+    fn modulus(&self) -> &IntegerAU {
+        &self.prime
+    }
+
+    fn to_internal(&self, v: &IntegerAU) -> IntegerAU {
+        v.clone()
+    }
+
+    fn to_canonical(&self, v: &IntegerAU) -> IntegerAU {
+        v.clone()
+    }
+
+    fn canonicalize(&self, v: IntegerAU) -> IntegerAU {
+        v
+    }
+
+    fn one(&self) -> IntegerAU {
+        IntegerAU::from(1)
+    }
+
+    fn mul(&self, a: &IntegerAU, b: &IntegerAU) -> IntegerAU {
+        self.reduce(&op_count::uncounted(|| a * b))
+    }
+
+    fn square(&self, a: &IntegerAU) -> IntegerAU {
+        self.reduce(&op_count::uncounted(|| a.square()))
+    }
+
+    fn mul_into(&self, a: &IntegerAU, b: &IntegerAU, out: &mut IntegerAU, scratch: &mut Scratch) {
+        let Scratch {
+            product,
+            a: q,
+            b: t,
+        } = scratch;
+        op_count::uncounted(|| IntegerAU::mul_into(a, b, product));
+        self.reduce_with(product, out, q, t);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::test_rng;
+    use crate::PRIMES;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_reduce_into() {
+        let mut rng = test_rng();
+        let mut scratch = Scratch::new();
+        let mut out = IntegerAU::from(0);
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            let barrett = Barrett::new(p.clone());
+            for _ in 0..100 {
+                let x = IntegerAU::random_below_with(&p, &mut rng);
+                let y = IntegerAU::random_below_with(&p, &mut rng);
+                let z = &x * &y;
+                barrett.reduce_into(&z, &mut out, &mut scratch);
+                assert_eq!(
+                    out.to_biguint(),
+                    z.to_biguint() % p.to_biguint(),
+                    "Failed reduce_into test: {z} mod {p}"
+                );
+                barrett.mul_into(&x, &y, &mut out, &mut scratch);
+                assert_eq!(out, barrett.mul(&x, &y));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            let barrett = Barrett::new(p.clone());
+            let x = &(&p - &IntegerAU::from(1)) * &(&p - &IntegerAU::from(2));
+            let json = serde_json::to_string(&barrett).unwrap();
+            let loaded: Barrett = serde_json::from_str(&json).unwrap();
+            assert_eq!(loaded.reduce(&x), barrett.reduce(&x));
+            let bytes = bincode::serialize(&barrett).unwrap();
+            let loaded: Barrett = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(loaded.barrett_mu, barrett.barrett_mu);
+
+            let mut tampered = barrett;
+            tampered.barrett_mu += &IntegerAU::from(1);
+            let json = serde_json::to_string(&tampered).unwrap();
+            assert!(serde_json::from_str::<Barrett>(&json).is_err());
+        }
+    }
 }
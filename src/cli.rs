@@ -0,0 +1,422 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Context;
+use clap::builder::RangedU64ValueParser;
+use clap::Parser;
+use clap::ValueEnum;
+
+use moduli_comparison::compare::compare;
+use moduli_comparison::integer_au::IntegerAU;
+use moduli_comparison::moduli::parse_modulus;
+use moduli_comparison::moduli::NAMED_MODULI;
+use moduli_comparison::prime::PrimeConstraints;
+use moduli_comparison::report::Record;
+use moduli_comparison::report::Report;
+use moduli_comparison::report::Stats;
+use moduli_comparison::seed;
+use moduli_comparison::seed::SEED_ENV;
+use moduli_comparison::workload;
+use moduli_comparison::workload::Inputs;
+use moduli_comparison::workload::Strategy;
+use moduli_comparison::workload::Workload;
+use moduli_comparison::PRIMES;
+
+// Default iterations per measurement of a sweep, which runs many moduli
+const SWEEP_ITERATIONS: usize = 100;
+
+/// Compare the performance of montgomery and barrett reductions against
+/// naïve reduction.
+///
+/// Run with `--bench` to run the divan benchmarks instead.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Args {
+    /// Moduli to benchmark, by name (see --list-moduli), decimal, or 0x
+    /// hex, 0b binary or 0o octal. Defaults to a set of 31 to 255 bit primes
+    #[arg(short, long = "modulus", value_delimiter = ',', value_parser = parse_modulus)]
+    pub moduli: Vec<IntegerAU>,
+
+    /// Reduction strategies to compare
+    #[arg(
+        short,
+        long = "reducer",
+        value_delimiter = ',',
+        default_value = "naive,barrett,montgomery,biguint"
+    )]
+    pub reducers: Vec<Strategy>,
+
+    /// Workloads to run: mul, mul-sum, pow, poseidon or ntt
+    #[arg(
+        short,
+        long = "workload",
+        value_delimiter = ',',
+        default_value = "mul,mul-sum"
+    )]
+    pub workloads: Vec<Workload>,
+
+    /// Iterations of each workload, defaults to a per workload count
+    #[arg(short = 'n', long, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub iterations: Option<usize>,
+
+    /// Untimed runs of each workload before the timed runs
+    #[arg(long, default_value_t = 3)]
+    pub warmup: usize,
+
+    /// Timed runs of each workload, statistics are computed over these
+    #[arg(short, long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    pub samples: u64,
+
+    /// log2 of the transform size used by the ntt workload
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    pub ntt_log_size: u32,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    /// File to write the output to instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Save the results as a baseline with this name
+    #[arg(long, value_name = "NAME", value_parser = parse_baseline_name)]
+    pub save_baseline: Option<String>,
+
+    /// Compare the results against the baseline with this name, exiting
+    /// with an error if any measurement regressed beyond the threshold
+    #[arg(short, long, value_name = "NAME", value_parser = parse_baseline_name)]
+    pub baseline: Option<String>,
+
+    /// Directory baselines are saved to and loaded from
+    #[arg(long, default_value = "target/baselines")]
+    pub baseline_dir: PathBuf,
+
+    /// Percent slowdown of a significant change that counts as a regression
+    #[arg(long, default_value_t = 5.0)]
+    pub regression_threshold: f64,
+
+    /// Benchmark random primes of every bit length from --sweep-min to
+    /// --sweep-max instead of --modulus, reporting a table of ns/op against
+    /// bit length. Iterations default to 100
+    #[arg(long, conflicts_with = "moduli")]
+    pub sweep: bool,
+
+    /// Smallest bit length of the sweep
+    #[arg(long, default_value_t = 8, value_parser = clap::value_parser!(u64).range(2..))]
+    pub sweep_min: u64,
+
+    /// Largest bit length of the sweep
+    #[arg(long, default_value_t = 4096)]
+    pub sweep_max: u64,
+
+    /// Bit length increment of the sweep
+    #[arg(long, default_value_t = 8, value_parser = clap::value_parser!(u64).range(1..))]
+    pub sweep_step: u64,
+
+    /// Minimum 2-adicity of the sweep primes, p = k * 2^s + 1
+    #[arg(long, default_value_t = 0, requires = "sweep")]
+    pub two_adicity: usize,
+
+    /// Sweep over Montgomery-friendly primes, p = -1 mod 2^64
+    #[arg(long, requires = "sweep")]
+    pub montgomery_friendly: bool,
+
+    /// Sweep over safe primes, (p - 1) / 2 is prime
+    #[arg(long, requires = "sweep")]
+    pub safe_prime: bool,
+
+    /// Seed for the random inputs and sweep primes, random if unset
+    #[arg(long, env = SEED_ENV)]
+    pub seed: Option<u64>,
+
+    /// Print the moduli that can be passed by name and exit
+    #[arg(long)]
+    pub list_moduli: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Prose, one line per measurement
+    Text,
+    /// A single markdown table
+    Markdown,
+    /// A JSON object with host information and an array of records
+    Json,
+    /// One row per record, with host information on every row
+    Csv,
+}
+
+pub fn run(args: &Args) -> anyhow::Result<()> {
+    if args.list_moduli {
+        for (name, value) in NAMED_MODULI {
+            println!("{name:<18} {value}");
+        }
+        return Ok(());
+    }
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let mut rng = seed::rng(seed);
+    let moduli = if args.sweep {
+        // bit lengths too small for the requested shape or without a prime
+        // of that shape are skipped
+        (args.sweep_min..=args.sweep_max)
+            .step_by(args.sweep_step as usize)
+            .map(|bits| PrimeConstraints {
+                bits: bits as usize,
+                two_adicity: args.two_adicity,
+                montgomery_friendly: args.montgomery_friendly,
+                safe: args.safe_prime,
+            })
+            .filter(|constraints| constraints.validate().is_ok())
+            .filter_map(|constraints| match constraints.generate(&mut rng) {
+                Ok(p) => Some(p),
+                Err(err) => {
+                    eprintln!("Skipped {} bits: {err}", constraints.bits);
+                    None
+                }
+            })
+            .collect()
+    } else if args.moduli.is_empty() {
+        PRIMES
+            .iter()
+            .map(|p| parse_modulus(p).map_err(anyhow::Error::msg))
+            .collect::<anyhow::Result<Vec<_>>>()?
+    } else {
+        args.moduli.clone()
+    };
+    // fail on a missing baseline before spending time on the benchmarks
+    let baseline = args
+        .baseline
+        .as_ref()
+        .map(|name| load_baseline(&args.baseline_dir, name))
+        .transpose()?;
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).with_context(|| format!("creating {}", path.display()))?,
+        )),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    // a sweep prints a single table instead of a line per measurement
+    let text = args.format == Format::Text && !args.sweep;
+    if text {
+        writeln!(out, "Inputs sampled with --seed {seed}")?;
+    }
+
+    let mut records = Vec::new();
+    for &workload in &args.workloads {
+        let iterations = match (args.iterations, args.sweep) {
+            (Some(iterations), _) => iterations,
+            (None, true) => SWEEP_ITERATIONS,
+            (None, false) => workload.default_iterations(),
+        };
+        if text {
+            print_intro(&mut out, workload)?;
+        }
+        for p in &moduli {
+            if text {
+                writeln!(out, "\n===== modulus {p} ({} bits) =====", p.bit_len())?;
+            }
+            let Some(inputs) = Inputs::sample(workload, p, iterations, args.ntt_log_size, &mut rng)
+            else {
+                if text {
+                    writeln!(
+                        out,
+                        "Skipped, p is not prime or 2^{} does not divide p - 1",
+                        args.ntt_log_size
+                    )?;
+                }
+                continue;
+            };
+            // every strategy is checked against the first one that ran
+            let mut expected: Option<(Strategy, Vec<IntegerAU>)> = None;
+            for &strategy in &args.reducers {
+                let Some(measurement) =
+                    workload::run(strategy, &inputs, args.warmup, args.samples as usize)
+                else {
+                    continue;
+                };
+                let stats = Stats::from_samples(&measurement.samples, iterations);
+                if text {
+                    writeln!(
+                        out,
+                        "{} time for {}: {:?} (median of {}, 95% CI {:?}..{:?}, {} outliers)",
+                        strategy.label(),
+                        workload.describe(iterations),
+                        per_sample(stats.median_ns, iterations),
+                        measurement.samples.len(),
+                        per_sample(stats.ci_low_ns, iterations),
+                        per_sample(stats.ci_high_ns, iterations),
+                        stats.outliers
+                    )?;
+                    if let Some(counts) = &measurement.counts {
+                        let [muls, adds, allocations, ops] = counts.per_reduction();
+                        writeln!(
+                            out,
+                            "  per reduction: {muls:.1} limb muls, {adds:.1} limb adds, \
+                             {allocations:.1} allocations, {ops:.1} integer ops"
+                        )?;
+                    }
+                }
+                match &expected {
+                    Some((reference, output)) => anyhow::ensure!(
+                        output == &measurement.output,
+                        "{strategy} reduction mismatches {reference} reduction, \
+                         rerun with --seed {seed} to reproduce"
+                    ),
+                    None => expected = Some((strategy, measurement.output)),
+                }
+                records.push(Record {
+                    modulus: p.to_string(),
+                    bits: p.bit_len(),
+                    strategy,
+                    workload,
+                    iterations,
+                    samples: measurement.samples.len(),
+                    stats,
+                    op_counts: measurement.counts,
+                });
+            }
+        }
+    }
+    let report = Report::new(seed, records);
+    match args.format {
+        Format::Text if args.sweep => write!(out, "{}", report.to_sweep_markdown())?,
+        Format::Text => {}
+        Format::Markdown if args.sweep => write!(out, "{}", report.to_sweep_markdown())?,
+        Format::Markdown => write!(out, "{}", report.to_markdown())?,
+        Format::Json => write!(out, "{}", report.to_json())?,
+        Format::Csv => write!(out, "{}", report.to_csv())?,
+    }
+    out.flush()?;
+
+    if let Some(name) = &args.save_baseline {
+        std::fs::create_dir_all(&args.baseline_dir)
+            .with_context(|| format!("creating {}", args.baseline_dir.display()))?;
+        let path = baseline_path(&args.baseline_dir, name);
+        std::fs::write(&path, report.to_csv())
+            .with_context(|| format!("writing {}", path.display()))?;
+    }
+    if let Some(baseline) = baseline {
+        // keep structured output parseable by reporting on stderr
+        let regressions = if matches!(args.format, Format::Json | Format::Csv) {
+            print_comparison(&mut std::io::stderr(), &baseline, &report, args)?
+        } else {
+            print_comparison(&mut out, &baseline, &report, args)?
+        };
+        out.flush()?;
+        anyhow::ensure!(
+            regressions == 0,
+            "{regressions} measurements regressed by more than {}% against baseline \"{}\"",
+            args.regression_threshold,
+            args.baseline.as_deref().unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+// Baselines are plain names, stored as csv reports in the baseline directory
+fn parse_baseline_name(name: &str) -> Result<String, String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+    {
+        return Err(format!(
+            "baseline name \"{name}\" may only contain letters, digits, '-', '_' and '.'"
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn baseline_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{name}.csv"))
+}
+
+fn load_baseline(dir: &Path, name: &str) -> anyhow::Result<Report> {
+    let path = baseline_path(dir, name);
+    let csv = std::fs::read_to_string(&path)
+        .with_context(|| format!("reading baseline {}", path.display()))?;
+    Report::from_csv(&csv)
+        .map_err(anyhow::Error::msg)
+        .with_context(|| format!("parsing baseline {}", path.display()))
+}
+
+// Prints one line per measurement found in the baseline and returns the
+// number of regressions
+fn print_comparison(
+    out: &mut impl Write,
+    baseline: &Report,
+    report: &Report,
+    args: &Args,
+) -> anyhow::Result<usize> {
+    let threshold = args.regression_threshold / 100.0;
+    let comparisons = compare(baseline, report);
+    writeln!(
+        out,
+        "\nComparison against baseline \"{}\" ({} on {})",
+        args.baseline.as_deref().unwrap_or_default(),
+        baseline.host.cpu,
+        baseline.host.hostname
+    )?;
+    let mut regressions = 0;
+    for c in &comparisons {
+        let verdict = if c.is_regression(threshold) {
+            regressions += 1;
+            "regressed"
+        } else if c.is_improvement(threshold) {
+            "improved"
+        } else if c.significant {
+            "changed within threshold"
+        } else {
+            "no significant change"
+        };
+        writeln!(
+            out,
+            "{} {} mod {} ({} bits): {:.1} -> {:.1} ns/op ({:+.1}%, {verdict})",
+            c.strategy.label(),
+            c.workload,
+            c.modulus,
+            c.bits,
+            c.baseline_ns,
+            c.current_ns,
+            c.change * 100.0
+        )?;
+    }
+    if comparisons.len() < report.records.len() {
+        writeln!(
+            out,
+            "{} measurements have no baseline",
+            report.records.len() - comparisons.len()
+        )?;
+    }
+    Ok(regressions)
+}
+
+// Time of a whole sample from a time per operation
+fn per_sample(ns_per_op: f64, iterations: usize) -> Duration {
+    Duration::from_nanos((ns_per_op * iterations as f64).round() as u64)
+}
+
+fn print_intro(out: &mut impl Write, workload: Workload) -> std::io::Result<()> {
+    let intro = match workload {
+        Workload::Mul => "Benchmarking multiplications between random values.",
+        Workload::MulSum => {
+            "Benchmarking multiplications and then summation\nbetween random values."
+        }
+        Workload::Pow => {
+            "Benchmarking exponentiations of random bases by\nrandom exponents below the modulus."
+        }
+        Workload::Poseidon => {
+            "Benchmarking Poseidon-like permutations with x^5\ns-boxes over a state of 3 elements."
+        }
+        Workload::Ntt => "Benchmarking radix 2 number theoretic transforms\nof random vectors.",
+    };
+    writeln!(out, "\n{intro}")?;
+    writeln!(out, "For Montgomery we assume the inputs are already in")?;
+    writeln!(out, "Montgomery form. The final result is converted to")?;
+    writeln!(out, "field representation.")
+}
@@ -0,0 +1,152 @@
+use super::report::Record;
+use super::report::Report;
+use super::workload::Strategy;
+use super::workload::Workload;
+
+/// Change of one (modulus, strategy, workload) measurement against a
+/// baseline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub modulus: String,
+    pub bits: usize,
+    pub strategy: Strategy,
+    pub workload: Workload,
+    /// Median ns per operation in the baseline
+    pub baseline_ns: f64,
+    /// Median ns per operation in the current run
+    pub current_ns: f64,
+    /// Relative change of the median, 0.1 is 10% slower
+    pub change: f64,
+    /// Whether the 95% confidence intervals of the medians are disjoint
+    pub significant: bool,
+}
+
+impl Comparison {
+    fn new(baseline: &Record, current: &Record) -> Self {
+        let (b, c) = (&baseline.stats, &current.stats);
+        Self {
+            modulus: current.modulus.clone(),
+            bits: current.bits,
+            strategy: current.strategy,
+            workload: current.workload,
+            baseline_ns: b.median_ns,
+            current_ns: c.median_ns,
+            change: (c.median_ns - b.median_ns) / b.median_ns,
+            significant: c.ci_low_ns > b.ci_high_ns || c.ci_high_ns < b.ci_low_ns,
+        }
+    }
+
+    /// A significant slowdown of more than `threshold`, e.g. 0.05 for 5%
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.significant && self.change > threshold
+    }
+
+    /// A significant speedup of more than `threshold`
+    pub fn is_improvement(&self, threshold: f64) -> bool {
+        self.significant && self.change < -threshold
+    }
+}
+
+/// Compares every record of `current` with the baseline record for the same
+/// modulus, strategy and workload
+/// Records missing from the baseline are skipped, as are records whose
+/// baseline median is zero or not finite since they have no relative change.
+pub fn compare(baseline: &Report, current: &Report) -> Vec<Comparison> {
+    current
+        .records
+        .iter()
+        .filter_map(|record| {
+            let base = baseline.records.iter().find(|b| {
+                b.modulus == record.modulus
+                    && b.strategy == record.strategy
+                    && b.workload == record.workload
+            })?;
+            let median = base.stats.median_ns;
+            if !median.is_finite() || median <= 0.0 {
+                return None;
+            }
+            Some(Comparison::new(base, record))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::HostInfo;
+    use crate::report::Stats;
+    use std::time::Duration;
+
+    fn record(strategy: Strategy, samples_ns: &[u64]) -> Record {
+        let samples = samples_ns
+            .iter()
+            .map(|&ns| Duration::from_nanos(ns))
+            .collect::<Vec<_>>();
+        Record {
+            modulus: "2013265921".to_string(),
+            bits: 31,
+            strategy,
+            workload: Workload::Mul,
+            iterations: 1,
+            samples: samples.len(),
+            stats: Stats::from_samples(&samples, 1),
+            op_counts: None,
+        }
+    }
+
+    fn report(records: Vec<Record>) -> Report {
+        Report {
+            host: HostInfo::current(),
+            seed: None,
+            records,
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = report(vec![
+            record(Strategy::Barrett, &[100, 101, 102, 103, 104]),
+            record(Strategy::Montgomery, &[100, 101, 102, 103, 104]),
+        ]);
+        let current = report(vec![
+            // 20% slower with disjoint intervals
+            record(Strategy::Barrett, &[120, 121, 122, 123, 124]),
+            // 20% faster with disjoint intervals
+            record(Strategy::Montgomery, &[80, 81, 82, 83, 84]),
+            // no baseline
+            record(Strategy::Naive, &[1000]),
+        ]);
+        let comparisons = compare(&baseline, &current);
+        assert_eq!(comparisons.len(), 2);
+        let (barrett, montgomery) = (&comparisons[0], &comparisons[1]);
+        assert!((barrett.change - 20.0 / 102.0).abs() < 1e-9);
+        assert!(barrett.is_regression(0.05));
+        assert!(!barrett.is_regression(0.25));
+        assert!(!barrett.is_improvement(0.05));
+        assert!(montgomery.is_improvement(0.05));
+        assert!(!montgomery.is_regression(0.05));
+    }
+
+    #[test]
+    fn test_noise_is_not_significant() {
+        let baseline = report(vec![record(Strategy::Barrett, &[90, 100, 110, 120, 130])]);
+        let current = report(vec![record(Strategy::Barrett, &[100, 110, 120, 130, 140])]);
+        let comparison = &compare(&baseline, &current)[0];
+        assert!(comparison.change > 0.05);
+        assert!(!comparison.significant);
+        assert!(!comparison.is_regression(0.05));
+    }
+
+    #[test]
+    fn test_degenerate_baseline() {
+        let zero = record(Strategy::Barrett, &[0, 0, 0]);
+        let mut nan = record(Strategy::Montgomery, &[100]);
+        nan.stats.median_ns = f64::NAN;
+        let baseline = report(vec![zero, nan]);
+        let current = report(vec![
+            record(Strategy::Barrett, &[100]),
+            record(Strategy::Montgomery, &[100]),
+        ]);
+        assert!(compare(&baseline, &current).is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+//! Branch-free helpers on fixed width limb slices
+//!
+//! Masks are either all zeros or all ones. Selecting with a mask avoids
+//! branching on secret data.
+
+use std::hint::black_box;
+
+/// Returns an all ones mask if `bit` is 1 and zero if it is 0
+/// The mask is passed through `black_box` so the optimizer can't see that it
+/// came from a single bit and turn the selects using it back into branches.
+pub fn mask(bit: u64) -> u64 {
+    black_box(0u64.wrapping_sub(bit & 1))
+}
+
+/// Computes a - b over the width of `a`, returning the difference and the
+/// final borrow (1 if b > a). Limbs of `b` past its length are treated as 0.
+pub fn sub_borrow(a: &[u64], b: &[u64]) -> (Vec<u64>, u64) {
+    let mut out = Vec::with_capacity(a.len());
+    let mut borrow = 0u64;
+    for (i, &x) in a.iter().enumerate() {
+        let y = b.get(i).copied().unwrap_or(0);
+        let (d1, o1) = x.overflowing_sub(y);
+        let (d2, o2) = d1.overflowing_sub(borrow);
+        out.push(d2);
+        borrow = (o1 | o2) as u64;
+    }
+    (out, borrow)
+}
+
+/// Returns `a` where the mask is set and `b` otherwise, limb by limb
+pub fn select(mask: u64, a: &[u64], b: &[u64]) -> Vec<u64> {
+    debug_assert_eq!(a.len(), b.len());
+    a.iter()
+        .zip(b)
+        .map(|(&x, &y)| (x & mask) | (y & !mask))
+        .collect()
+}
+
+/// Swaps `a` and `b` if the mask is set
+pub fn cswap(mask: u64, a: &mut [u64], b: &mut [u64]) {
+    debug_assert_eq!(a.len(), b.len());
+    for (x, y) in a.iter_mut().zip(b.iter_mut()) {
+        let t = (*x ^ *y) & mask;
+        *x ^= t;
+        *y ^= t;
+    }
+}
+
+/// Full product of two limb slices without trimming
+/// The result always has a.len() + b.len() limbs.
+pub fn mul(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut result = vec![0u64; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let t = result[i + j] as u128 + (x as u128) * (y as u128) + carry as u128;
+            result[i + j] = t as u64;
+            carry = (t >> 64) as u64;
+        }
+        result[i + b.len()] = carry;
+    }
+    result
+}
+
+/// Sum of two limb slices with max(a.len(), b.len()) + 1 limbs
+pub fn add(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let len = a.len().max(b.len());
+    let mut result = Vec::with_capacity(len + 1);
+    let mut carry = 0u64;
+    for i in 0..len {
+        let x = a.get(i).copied().unwrap_or(0);
+        let y = b.get(i).copied().unwrap_or(0);
+        let (s1, o1) = x.overflowing_add(y);
+        let (s2, o2) = s1.overflowing_add(carry);
+        result.push(s2);
+        carry = (o1 | o2) as u64;
+    }
+    result.push(carry);
+    result
+}
+
+/// Bitwise AND with a mask, the result has mask.len() limbs
+pub fn and(a: &[u64], mask: &[u64]) -> Vec<u64> {
+    mask.iter()
+        .enumerate()
+        .map(|(i, &m)| a.get(i).copied().unwrap_or(0) & m)
+        .collect()
+}
+
+/// Right shift by a public amount, the result has `width` limbs
+pub fn shr(a: &[u64], shift: usize, width: usize) -> Vec<u64> {
+    let words = shift / 64;
+    let bits = shift % 64;
+    (0..width)
+        .map(|i| {
+            let lo = a.get(i + words).copied().unwrap_or(0);
+            if bits == 0 {
+                lo
+            } else {
+                let hi = a.get(i + words + 1).copied().unwrap_or(0);
+                (lo >> bits) | (hi << (64 - bits))
+            }
+        })
+        .collect()
+}
+
+/// Zero extends limbs to exactly `width` limbs
+pub fn pad(mut limbs: Vec<u64>, width: usize) -> Vec<u64> {
+    debug_assert!(limbs.len() <= width || limbs[width..].iter().all(|&l| l == 0));
+    limbs.resize(width, 0);
+    limbs
+}
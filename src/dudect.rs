@@ -0,0 +1,207 @@
+//! dudect style timing leakage detection
+//!
+//! Inputs are split in two classes, typically a fixed input and random
+//! inputs. Measurements of both classes are interleaved in a random order and
+//! compared with Welch's t-test. A large |t| means the execution time depends
+//! on the input class. See "Dude, is my code constant time?" (Reparaz,
+//! Balasch, Verbauwhede 2017).
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use rand::Rng;
+
+use super::seed;
+
+/// |t| values above this threshold are considered evidence of a leak
+pub const LEAK_THRESHOLD: f64 = 4.5;
+
+// Measurements above these percentiles are cropped in additional tests to
+// remove the long tail caused by interrupts and cache misses
+const CROP_PERCENTILES: [f64; 5] = [0.5, 0.75, 0.9, 0.95, 0.99];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Fixed,
+    Random,
+}
+
+/// Online mean and variance using Welford's method
+#[derive(Debug, Clone, Default)]
+pub struct Welford {
+    n: f64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    pub fn push(&mut self, x: f64) {
+        self.n += 1.0;
+        let delta = x - self.mean;
+        self.mean += delta / self.n;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    pub fn variance(&self) -> f64 {
+        if self.n < 2.0 {
+            0.0
+        } else {
+            self.m2 / (self.n - 1.0)
+        }
+    }
+}
+
+/// Welch's t statistic between two sets of measurements
+pub fn welch_t(a: &Welford, b: &Welford) -> f64 {
+    let denom = (a.variance() / a.n + b.variance() / b.n).sqrt();
+    if denom == 0.0 {
+        0.0
+    } else {
+        (a.mean - b.mean) / denom
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DudectResult {
+    /// Largest |t| over the uncropped and cropped tests
+    pub max_t: f64,
+    /// Number of measurements taken
+    pub samples: usize,
+}
+
+impl DudectResult {
+    pub fn leaks(&self) -> bool {
+        self.max_t > LEAK_THRESHOLD
+    }
+}
+
+/// Times `op` on `samples` inputs produced by `prepare`, each assigned to a
+/// random class, and tests whether the two classes have different timing
+/// distributions. Inputs are generated before timing starts.
+pub fn measure<I, P, F, O>(samples: usize, mut prepare: P, mut op: F) -> DudectResult
+where
+    P: FnMut(Class) -> I,
+    F: FnMut(&I) -> O,
+{
+    // separate from the thread's RNG, which `prepare` may use
+    let mut rng = seed::rng(seed::global_seed());
+    let inputs = (0..samples)
+        .map(|_| {
+            let class = if rng.gen::<bool>() {
+                Class::Fixed
+            } else {
+                Class::Random
+            };
+            (class, prepare(class))
+        })
+        .collect::<Vec<_>>();
+
+    let mut timings = Vec::with_capacity(samples);
+    for (class, input) in &inputs {
+        let start = Instant::now();
+        black_box(op(black_box(input)));
+        timings.push((*class, start.elapsed().as_nanos() as f64));
+    }
+
+    let mut sorted = timings.iter().map(|(_, t)| *t).collect::<Vec<_>>();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mut thresholds = vec![f64::INFINITY];
+    for pct in CROP_PERCENTILES {
+        thresholds.push(sorted[((sorted.len() - 1) as f64 * pct) as usize]);
+    }
+
+    let max_t = thresholds
+        .iter()
+        .map(|&threshold| {
+            let mut fixed = Welford::default();
+            let mut random = Welford::default();
+            for (class, t) in timings.iter().filter(|(_, t)| *t <= threshold) {
+                match class {
+                    Class::Fixed => fixed.push(*t),
+                    Class::Random => random.push(*t),
+                }
+            }
+            welch_t(&fixed, &random).abs()
+        })
+        .fold(0.0, f64::max);
+
+    DudectResult { max_t, samples }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integer_au::IntegerAU;
+    use crate::montgomery::Montgomery;
+    use crate::reducer::Reducer;
+    use crate::seed::test_rng;
+    use crate::PRIMES;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_welch_t() {
+        let mut a = Welford::default();
+        let mut b = Welford::default();
+        for i in 0..1000 {
+            a.push((i % 10) as f64);
+            b.push((i % 10) as f64);
+        }
+        assert_eq!(welch_t(&a, &b), 0.0);
+
+        let mut c = Welford::default();
+        for i in 0..1000 {
+            c.push((i % 10) as f64 + 5.0);
+        }
+        assert!(welch_t(&a, &c) < -LEAK_THRESHOLD);
+        assert!(welch_t(&c, &a) > LEAK_THRESHOLD);
+    }
+
+    // Wall-clock timing like test_ladder_timing, a loaded machine can hide
+    // the leak: cargo test --release -- --ignored
+    #[test]
+    #[ignore]
+    fn test_detects_square_and_multiply() {
+        let mut rng = test_rng();
+        let p = IntegerAU::from_str(PRIMES[4]).unwrap();
+        let montgomery = Montgomery::new(&p);
+        let base = montgomery.to_internal(&IntegerAU::random_below_with(&p, &mut rng));
+        // a single set bit versus random exponents of the same length
+        let top = &IntegerAU::from(1) << 255;
+        let result = measure(
+            2000,
+            |class| match class {
+                Class::Fixed => top.clone(),
+                Class::Random => &IntegerAU::random_below_with(&top, &mut rng) | &top,
+            },
+            |exp| montgomery.pow(&base, exp),
+        );
+        assert!(result.leaks(), "t = {}", result.max_t);
+    }
+
+    // Timing measurements depend on the machine and its load so this is
+    // not run by default: cargo test --release -- --ignored
+    #[test]
+    #[ignore]
+    fn test_ladder_timing() {
+        let mut rng = test_rng();
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            let montgomery = Montgomery::new(&p);
+            let exp_bits = p.bit_len();
+            let fixed_base = montgomery.to_internal(&IntegerAU::from(1));
+            let exp = &p - &IntegerAU::from(2);
+            let result = measure(
+                20000,
+                |class| match class {
+                    Class::Fixed => fixed_base.clone(),
+                    Class::Random => {
+                        montgomery.to_internal(&IntegerAU::random_below_with(&p, &mut rng))
+                    }
+                },
+                |base| montgomery.pow_ladder(base, &exp, exp_bits),
+            );
+            println!("modulus {p}: max |t| = {:.2}", result.max_t);
+            assert!(!result.leaks(), "modulus {p}: t = {}", result.max_t);
+        }
+    }
+}
@@ -0,0 +1,528 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::Add;
+use std::ops::Div;
+use std::ops::Mul;
+use std::ops::Neg;
+use std::ops::Rem;
+use std::ops::Sub;
+use std::str::FromStr;
+
+use num_bigint::BigInt;
+use num_bigint::Sign;
+
+use super::integer_au::IntegerAU;
+use super::integer_au::ParseIntegerError;
+
+/// Arbitrary precision signed integer, a sign and an IntegerAU magnitude
+/// Zero is never negative
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntegerAS {
+    negative: bool,
+    magnitude: IntegerAU,
+}
+
+impl IntegerAS {
+    /// The value with the given sign and magnitude, negative zero is zero
+    pub fn new(negative: bool, magnitude: IntegerAU) -> Self {
+        Self {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    /// The absolute value
+    pub fn magnitude(&self) -> &IntegerAU {
+        &self.magnitude
+    }
+
+    /// The sign and absolute value
+    pub fn into_parts(self) -> (bool, IntegerAU) {
+        (self.negative, self.magnitude)
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::new(false, self.magnitude.clone())
+    }
+
+    /// -1, 0 or 1 following the sign
+    pub fn signum(&self) -> i64 {
+        match (self.negative, self.is_zero()) {
+            (true, _) => -1,
+            (false, true) => 0,
+            (false, false) => 1,
+        }
+    }
+
+    /// Quotient rounded towards zero and remainder with the sign of self,
+    /// like `/` and `%` on primitive integers
+    /// Panics if other is zero
+    pub fn div_rem_trunc(&self, other: &Self) -> (Self, Self) {
+        let quotient = &self.magnitude / &other.magnitude;
+        let remainder = self.magnitude.modulo(&other.magnitude).unwrap();
+        (
+            Self::new(self.negative != other.negative, quotient),
+            Self::new(self.negative, remainder),
+        )
+    }
+
+    /// Quotient rounded towards negative infinity and remainder with the
+    /// sign of other
+    /// Panics if other is zero
+    pub fn div_rem_floor(&self, other: &Self) -> (Self, Self) {
+        let (quotient, remainder) = self.div_rem_trunc(other);
+        if !remainder.is_zero() && self.negative != other.negative {
+            (&quotient - &Self::from(1), &remainder + other)
+        } else {
+            (quotient, remainder)
+        }
+    }
+
+    /// Quotient rounded towards negative infinity
+    pub fn div_floor(&self, other: &Self) -> Self {
+        self.div_rem_floor(other).0
+    }
+
+    /// Remainder with the sign of other, in [0, other) for positive other
+    pub fn mod_floor(&self, other: &Self) -> Self {
+        self.div_rem_floor(other).1
+    }
+
+    /// Returns (g, x, y) with g = gcd(self, other) >= 0 and
+    /// self * x + other * y = g
+    pub fn extended_gcd(&self, other: &Self) -> (Self, Self, Self) {
+        let (mut r0, mut r1) = (self.clone(), other.clone());
+        let (mut x0, mut x1) = (Self::from(1), Self::from(0));
+        let (mut y0, mut y1) = (Self::from(0), Self::from(1));
+        while !r1.is_zero() {
+            let (q, r) = r0.div_rem_floor(&r1);
+            (r0, r1) = (r1, r);
+            let x = &x0 - &(&q * &x1);
+            (x0, x1) = (x1, x);
+            let y = &y0 - &(&q * &y1);
+            (y0, y1) = (y1, y);
+        }
+        if r0.is_negative() {
+            (-r0, -x0, -y0)
+        } else {
+            (r0, x0, y0)
+        }
+    }
+
+    pub fn to_bigint(&self) -> BigInt {
+        let sign = if self.negative {
+            Sign::Minus
+        } else {
+            Sign::Plus
+        };
+        BigInt::from_biguint(sign, self.magnitude.to_biguint())
+    }
+
+    pub fn from_bigint(v: &BigInt) -> Self {
+        Self::new(
+            v.sign() == Sign::Minus,
+            IntegerAU::from_biguint(v.magnitude().clone()),
+        )
+    }
+}
+
+impl From<IntegerAU> for IntegerAS {
+    fn from(v: IntegerAU) -> Self {
+        Self::new(false, v)
+    }
+}
+
+impl From<i64> for IntegerAS {
+    fn from(v: i64) -> Self {
+        Self::new(v < 0, IntegerAU::from(v.unsigned_abs()))
+    }
+}
+
+impl TryFrom<IntegerAS> for IntegerAU {
+    type Error = IntegerAS;
+
+    /// Fails with the value itself if it is negative
+    fn try_from(v: IntegerAS) -> Result<IntegerAU, IntegerAS> {
+        if v.negative {
+            Err(v)
+        } else {
+            Ok(v.magnitude)
+        }
+    }
+}
+
+impl FromStr for IntegerAS {
+    type Err = ParseIntegerError;
+
+    /// Parses an optional sign followed by anything `IntegerAU::from_str`
+    /// accepts
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let offset = s.len() - digits.len();
+        let magnitude = IntegerAU::from_str(digits).map_err(|e| match e {
+            ParseIntegerError::InvalidDigit {
+                digit,
+                index,
+                radix,
+            } => ParseIntegerError::InvalidDigit {
+                digit,
+                index: index + offset,
+                radix,
+            },
+            e => e,
+        })?;
+        Ok(Self::new(negative, magnitude))
+    }
+}
+
+impl Display for IntegerAS {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_decimal_string())
+    }
+}
+
+impl PartialOrd for IntegerAS {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for IntegerAS {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl Neg for IntegerAS {
+    type Output = IntegerAS;
+
+    fn neg(self) -> IntegerAS {
+        Self::new(!self.negative, self.magnitude)
+    }
+}
+
+impl Neg for &IntegerAS {
+    type Output = IntegerAS;
+
+    fn neg(self) -> IntegerAS {
+        -self.clone()
+    }
+}
+
+impl<'b> Add<&'b IntegerAS> for &IntegerAS {
+    type Output = IntegerAS;
+
+    fn add(self, other: &'b IntegerAS) -> IntegerAS {
+        if self.negative == other.negative {
+            return IntegerAS::new(self.negative, &self.magnitude + &other.magnitude);
+        }
+        // Opposite signs, the larger magnitude decides the sign
+        match self.magnitude.cmp(&other.magnitude) {
+            Ordering::Less => IntegerAS::new(other.negative, &other.magnitude - &self.magnitude),
+            _ => IntegerAS::new(self.negative, &self.magnitude - &other.magnitude),
+        }
+    }
+}
+
+impl<'b> Sub<&'b IntegerAS> for &IntegerAS {
+    type Output = IntegerAS;
+
+    fn sub(self, other: &'b IntegerAS) -> IntegerAS {
+        self + &-other
+    }
+}
+
+impl<'b> Mul<&'b IntegerAS> for &IntegerAS {
+    type Output = IntegerAS;
+
+    fn mul(self, other: &'b IntegerAS) -> IntegerAS {
+        IntegerAS::new(
+            self.negative != other.negative,
+            &self.magnitude * &other.magnitude,
+        )
+    }
+}
+
+// Truncating division, like primitive integers
+impl<'b> Div<&'b IntegerAS> for &IntegerAS {
+    type Output = IntegerAS;
+
+    fn div(self, other: &'b IntegerAS) -> IntegerAS {
+        self.div_rem_trunc(other).0
+    }
+}
+
+// Remainder of truncating division, with the sign of the dividend
+impl<'b> Rem<&'b IntegerAS> for &IntegerAS {
+    type Output = IntegerAS;
+
+    fn rem(self, other: &'b IntegerAS) -> IntegerAS {
+        self.div_rem_trunc(other).1
+    }
+}
+
+// Owned and mixed operands delegate to the reference implementations
+forward_owned_binop! {
+    IntegerAS;
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    Div, div;
+    Rem, rem;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::test_rng;
+    use rand::Rng;
+
+    // Mixes of signs, magnitudes around limb boundaries and zero
+    const CASES: [(&str, &str); 10] = [
+        ("0", "0"),
+        ("0", "-1"),
+        ("42", "58"),
+        ("-42", "58"),
+        ("42", "-58"),
+        ("-42", "-58"),
+        ("18446744073709551615", "-1"),
+        ("-18446744073709551616", "18446744073709551615"),
+        ("34893458934589345893458934", "-89345893458934589345893458"),
+        ("-89345893458934589345893458", "-34893458934589345893458934"),
+    ];
+
+    fn parse_pair(a: &str, b: &str) -> (IntegerAS, IntegerAS, BigInt, BigInt) {
+        let a_big = BigInt::from_str(a).unwrap();
+        let b_big = BigInt::from_str(b).unwrap();
+        (
+            IntegerAS::from_str(a).unwrap(),
+            IntegerAS::from_str(b).unwrap(),
+            a_big,
+            b_big,
+        )
+    }
+
+    #[test]
+    fn test_addition() {
+        for (a_str, b_str) in CASES {
+            let (a, b, a_big, b_big) = parse_pair(a_str, b_str);
+            assert_eq!(
+                (&a + &b).to_bigint(),
+                &a_big + &b_big,
+                "Failed addition test: {} + {}",
+                a_str,
+                b_str
+            );
+        }
+    }
+
+    #[test]
+    fn test_subtraction() {
+        for (a_str, b_str) in CASES {
+            let (a, b, a_big, b_big) = parse_pair(a_str, b_str);
+            assert_eq!(
+                (&a - &b).to_bigint(),
+                &a_big - &b_big,
+                "Failed subtraction test: {} - {}",
+                a_str,
+                b_str
+            );
+            assert_eq!((&b - &a).to_bigint(), &b_big - &a_big);
+        }
+    }
+
+    #[test]
+    fn test_multiplication() {
+        for (a_str, b_str) in CASES {
+            let (a, b, a_big, b_big) = parse_pair(a_str, b_str);
+            assert_eq!(
+                (&a * &b).to_bigint(),
+                &a_big * &b_big,
+                "Failed multiplication test: {} * {}",
+                a_str,
+                b_str
+            );
+        }
+        // a zero product is never negative
+        assert!(!(IntegerAS::from(-5) * IntegerAS::from(0)).is_negative());
+    }
+
+    #[test]
+    fn test_division() {
+        for (a_str, b_str) in CASES {
+            let (a, b, a_big, b_big) = parse_pair(a_str, b_str);
+            if b.is_zero() {
+                continue;
+            }
+            let (q, r) = a.div_rem_trunc(&b);
+            assert_eq!(
+                q.to_bigint(),
+                &a_big / &b_big,
+                "Failed division test: {} / {}",
+                a_str,
+                b_str
+            );
+            assert_eq!(r.to_bigint(), &a_big % &b_big);
+            assert_eq!(&a / &b, q);
+            assert_eq!(&a % &b, r);
+
+            // floor division, from the truncated BigInt quotient
+            let mut q_floor = &a_big / &b_big;
+            let mut r_floor = &a_big % &b_big;
+            if r_floor != BigInt::from(0) && (a_big < BigInt::from(0)) != (b_big < BigInt::from(0))
+            {
+                q_floor -= 1;
+                r_floor += &b_big;
+            }
+            let (q, r) = a.div_rem_floor(&b);
+            assert_eq!(q.to_bigint(), q_floor, "Failed floor division test");
+            assert_eq!(r.to_bigint(), r_floor);
+            assert_eq!(&(&q * &b) + &r, a);
+        }
+        assert_eq!(
+            IntegerAS::from(-7).div_floor(&IntegerAS::from(2)),
+            IntegerAS::from(-4)
+        );
+        assert_eq!(
+            IntegerAS::from(-7).mod_floor(&IntegerAS::from(2)),
+            IntegerAS::from(1)
+        );
+        assert_eq!(
+            IntegerAS::from(7).mod_floor(&IntegerAS::from(-2)),
+            IntegerAS::from(-1)
+        );
+        assert_eq!(
+            IntegerAS::from(-7) / IntegerAS::from(2),
+            IntegerAS::from(-3)
+        );
+        assert_eq!(
+            IntegerAS::from(-7) % IntegerAS::from(2),
+            IntegerAS::from(-1)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by 0")]
+    fn test_division_by_zero() {
+        let _ = IntegerAS::from(1) / IntegerAS::from(0);
+    }
+
+    #[test]
+    fn test_random_arithmetic() {
+        let mut rng = test_rng();
+        let bound = &IntegerAU::from(1) << 300;
+        let mut random =
+            || IntegerAS::new(rng.gen(), IntegerAU::random_below_with(&bound, &mut rng));
+        for _ in 0..100 {
+            let (a, b) = (random(), random());
+            let (a_big, b_big) = (a.to_bigint(), b.to_bigint());
+            assert_eq!(IntegerAS::from_bigint(&a_big), a);
+            assert_eq!((&a + &b).to_bigint(), &a_big + &b_big);
+            assert_eq!((&a - &b).to_bigint(), &a_big - &b_big);
+            assert_eq!((&a * &b).to_bigint(), &a_big * &b_big);
+            if !b.is_zero() {
+                assert_eq!((&a / &b).to_bigint(), &a_big / &b_big);
+                assert_eq!((&a % &b).to_bigint(), &a_big % &b_big);
+            }
+            assert_eq!(a.cmp(&b), a_big.cmp(&b_big));
+        }
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut values = [
+            "5",
+            "-3",
+            "0",
+            "-18446744073709551616",
+            "18446744073709551616",
+            "-4",
+        ]
+        .map(|s| IntegerAS::from_str(s).unwrap());
+        values.sort();
+        let sorted = values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            sorted,
+            [
+                "-18446744073709551616",
+                "-4",
+                "-3",
+                "0",
+                "5",
+                "18446744073709551616"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(IntegerAS::from_str("-0x10").unwrap(), IntegerAS::from(-16));
+        assert_eq!(IntegerAS::from_str("+12").unwrap(), IntegerAS::from(12));
+        assert_eq!(IntegerAS::from_str("-0").unwrap(), IntegerAS::from(0));
+        assert!(!IntegerAS::from_str("-0").unwrap().is_negative());
+        assert_eq!(
+            IntegerAS::from_str("-1a"),
+            Err(ParseIntegerError::InvalidDigit {
+                digit: 'a',
+                index: 2,
+                radix: 10
+            })
+        );
+        assert!(IntegerAS::from_str("-").is_err());
+        assert!(IntegerAS::from_str("--1").is_err());
+        assert_eq!(IntegerAS::from(-42).to_string(), "-42");
+        assert_eq!(format!("{:>5}", IntegerAS::from(-42)), "  -42");
+        assert_eq!(format!("{:+}", IntegerAS::from(42)), "+42");
+        assert_eq!(IntegerAS::from(i64::MIN).to_string(), i64::MIN.to_string());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let v = IntegerAU::from(7);
+        assert_eq!(IntegerAU::try_from(IntegerAS::from(v.clone())), Ok(v));
+        assert!(IntegerAU::try_from(IntegerAS::from(-7)).is_err());
+        assert_eq!(IntegerAS::from(-7).abs(), IntegerAS::from(7));
+        assert_eq!(IntegerAS::from(-7).signum(), -1);
+        assert_eq!(IntegerAS::from(0).signum(), 0);
+        assert_eq!(-IntegerAS::from(0), IntegerAS::from(0));
+        assert_eq!(-&IntegerAS::from(3), IntegerAS::from(-3));
+        assert_eq!(
+            IntegerAS::from_bigint(&BigInt::from(-9)),
+            IntegerAS::from(-9)
+        );
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        let cases = [
+            (240, 46, 2),
+            (-240, 46, 2),
+            (17, -5, 1),
+            (0, 9, 9),
+            (9, 0, 9),
+            (-12, -18, 6),
+        ];
+        for (a, b, expected) in cases {
+            let (a, b) = (IntegerAS::from(a), IntegerAS::from(b));
+            let (g, x, y) = a.extended_gcd(&b);
+            assert_eq!(g, IntegerAS::from(expected), "Failed gcd test: {a}, {b}");
+            assert_eq!(&(&a * &x) + &(&b * &y), g);
+        }
+    }
+}
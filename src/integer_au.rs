@@ -1,43 +1,86 @@
 use std::cmp::PartialEq;
 use std::cmp::PartialOrd;
+use std::fmt::Binary;
 use std::fmt::Display;
+use std::fmt::LowerHex;
+use std::fmt::Octal;
+use std::fmt::UpperHex;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::iter::Product;
+use std::iter::Sum;
 use std::ops::Add;
 use std::ops::AddAssign;
 use std::ops::BitAnd;
+use std::ops::BitAndAssign;
 use std::ops::BitOr;
+use std::ops::BitOrAssign;
+use std::ops::BitXor;
+use std::ops::BitXorAssign;
 use std::ops::Div;
 use std::ops::Mul;
+use std::ops::MulAssign;
 use std::ops::Rem;
 use std::ops::Shl;
+use std::ops::ShlAssign;
 use std::ops::Shr;
+use std::ops::ShrAssign;
 use std::ops::Sub;
+use std::ops::SubAssign;
+use std::str::FromStr;
 
 use num_bigint::BigUint;
+use rand::distributions::uniform::SampleBorrow;
+use rand::distributions::uniform::SampleUniform;
+use rand::distributions::uniform::UniformSampler;
 use rand::Rng;
 
+use super::op_count;
+use super::seed;
+
 /// Arbitrary precision unsigned integer using 64 bit limbs
 /// All operations are done naively
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct IntegerAU {
     pub limbs: Vec<u64>,
 }
 
+impl Clone for IntegerAU {
+    fn clone(&self) -> Self {
+        Self {
+            limbs: self.limbs.clone(),
+        }
+    }
+
+    // Reuses the existing limb buffer when it is large enough
+    fn clone_from(&mut self, source: &Self) {
+        self.limbs.clone_from(&source.limbs);
+    }
+}
+
 impl IntegerAU {
     /// Samples a random value uniformly between 0 and upper (exclusive)
-    /// Returns None if upper is zero
+    /// using the thread's RNG seeded with `seed::global_seed`
+    /// Panics if upper is zero
     pub fn random_below(upper: &Self) -> Self {
+        seed::with_thread_rng(|rng| Self::random_below_with(upper, rng))
+    }
+
+    /// Samples a random value uniformly between 0 and upper (exclusive)
+    /// from `rng`, seed it for reproducible values
+    /// Panics if upper is zero
+    pub fn random_below_with<R: Rng + ?Sized>(upper: &Self, rng: &mut R) -> Self {
         // Handle zero upper bound
         if upper.limbs.len() == 1 && upper.limbs[0] == 0 {
             panic!("Upper bound must be non-zero")
         }
 
-        let mut rng = rand::thread_rng();
         let bit_len = upper.bit_len();
 
         // We'll use rejection sampling to get uniform distribution
         loop {
             // Calculate how many limbs we need
-            let num_limbs = (bit_len + 63) / 64;
+            let num_limbs = bit_len.div_ceil(64);
             let mut limbs = Vec::with_capacity(num_limbs);
 
             // Generate random limbs
@@ -105,6 +148,100 @@ impl IntegerAU {
         complete_limbs_bits + msb_bits
     }
 
+    pub fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|&limb| limb == 0)
+    }
+
+    /// Returns the value of bit i, counting from the least significant bit
+    pub fn bit(&self, i: usize) -> bool {
+        match self.limbs.get(i / 64) {
+            Some(limb) => (limb >> (i % 64)) & 1 == 1,
+            None => false,
+        }
+    }
+
+    /// Sets bit i to one, growing the value if needed
+    pub fn set_bit(&mut self, i: usize) {
+        let limb = i / 64;
+        if limb >= self.limbs.len() {
+            self.limbs.resize(limb + 1, 0);
+        }
+        self.limbs[limb] |= 1 << (i % 64);
+    }
+
+    /// Sets bit i to zero
+    pub fn clear_bit(&mut self, i: usize) {
+        if let Some(limb) = self.limbs.get_mut(i / 64) {
+            *limb &= !(1 << (i % 64));
+            self.normalize();
+        }
+    }
+
+    /// Number of set bits
+    pub fn count_ones(&self) -> usize {
+        self.limbs
+            .iter()
+            .map(|limb| limb.count_ones() as usize)
+            .sum()
+    }
+
+    /// Number of zero bits below the lowest set bit, None for zero
+    pub fn trailing_zeros(&self) -> Option<usize> {
+        let i = self.limbs.iter().position(|&limb| limb != 0)?;
+        Some(i * 64 + self.limbs[i].trailing_zeros() as usize)
+    }
+
+    /// Number of zero bits above the highest set bit in a `bits` wide value,
+    /// zero if the value does not fit
+    pub fn leading_zeros(&self, bits: usize) -> usize {
+        bits.saturating_sub(self.bit_len())
+    }
+
+    /// Complement of the low `bits` bits, higher bits are dropped
+    pub fn not(&self, bits: usize) -> Self {
+        let len = bits.div_ceil(64);
+        let mut limbs = (0..len)
+            .map(|i| !self.limbs.get(i).copied().unwrap_or(0))
+            .collect::<Vec<_>>();
+        if !bits.is_multiple_of(64) {
+            limbs[len - 1] &= (1 << (bits % 64)) - 1;
+        }
+        IntegerAU { limbs }.trim()
+    }
+
+    /// Reads `width` <= 64 bits starting at bit `offset`
+    pub fn bits_at(&self, offset: usize, width: usize) -> u64 {
+        assert!(width <= 64, "at most 64 bits can be read at once");
+        if width == 0 {
+            return 0;
+        }
+        let (limb, shift) = (offset / 64, offset % 64);
+        let mut v = self.limbs.get(limb).copied().unwrap_or(0) >> shift;
+        if shift != 0 && shift + width > 64 {
+            v |= self.limbs.get(limb + 1).copied().unwrap_or(0) << (64 - shift);
+        }
+        if width < 64 {
+            v &= (1 << width) - 1;
+        }
+        v
+    }
+
+    /// Bits from the least significant up to the highest set bit, reverse
+    /// the iterator for most significant first
+    pub fn bits(&self) -> impl DoubleEndedIterator<Item = bool> + ExactSizeIterator + '_ {
+        (0..self.bit_len()).map(|i| self.bit(i))
+    }
+
+    /// Consecutive `width` bit digits from the least significant end, the
+    /// last one zero padded. Reverse the iterator for most significant first
+    pub fn windows(
+        &self,
+        width: usize,
+    ) -> impl DoubleEndedIterator<Item = u64> + ExactSizeIterator + '_ {
+        assert!((1..=64).contains(&width), "window must be between 1 and 64");
+        (0..self.bit_len().div_ceil(width)).map(move |d| self.bits_at(d * width, width))
+    }
+
     /// Performs modular reduction self mod m
     /// Returns None if m is zero
     pub fn modulo(&self, m: &Self) -> Option<Self> {
@@ -140,34 +277,231 @@ impl IntegerAU {
         // Subtract from largest to smallest
         for shifted_m in shifts.iter().rev() {
             if shifted_m <= &result {
-                result = &result - &shifted_m;
+                result = &result - shifted_m;
             }
         }
 
         Some(result)
     }
 
-    // Helper function to compare two numbers
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        if self.limbs.len() != other.limbs.len() {
-            return self.limbs.len().cmp(&other.limbs.len());
+    pub fn trim(mut self) -> Self {
+        self.normalize();
+        self
+    }
+
+    // Removes leading zero limbs in place, an empty value becomes zero
+    fn normalize(&mut self) {
+        while self.limbs.len() > 1 && self.limbs[self.limbs.len() - 1] == 0 {
+            self.limbs.pop();
+        }
+        if self.limbs.is_empty() {
+            self.limbs.push(0);
         }
+    }
 
-        // Compare limbs from most significant to least significant
-        for i in (0..self.limbs.len()).rev() {
-            if self.limbs[i] != other.limbs[i] {
-                return self.limbs[i].cmp(&other.limbs[i]);
+    /// self - other, None if other is larger
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        let width = self.limbs.len().max(other.limbs.len());
+        let (result, borrow) = sub_limbs(&self.limbs, &other.limbs, width);
+        (!borrow).then(|| IntegerAU { limbs: result }.trim())
+    }
+
+    /// self - other, zero if other is larger
+    pub fn saturating_sub(&self, other: &Self) -> Self {
+        self.checked_sub(other).unwrap_or_default()
+    }
+
+    /// |self - other|
+    pub fn abs_diff(&self, other: &Self) -> Self {
+        if self >= other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+
+    /// (self + other) mod 2^(64 limbs) and whether the sum reached
+    /// 2^(64 limbs), for operands that fit in `limbs` limbs
+    pub fn overflowing_add(&self, other: &Self, limbs: usize) -> (Self, bool) {
+        let mut result = Vec::with_capacity(limbs);
+        let mut carry = false;
+        op_count::record(0, limbs, 1);
+        for i in 0..limbs {
+            let a = self.limbs.get(i).copied().unwrap_or(0);
+            let b = other.limbs.get(i).copied().unwrap_or(0);
+            let (sum, c1) = a.overflowing_add(b);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            result.push(sum);
+            carry = c1 || c2;
+        }
+        (IntegerAU { limbs: result }.trim(), carry)
+    }
+
+    /// (self + other) mod 2^(64 limbs)
+    pub fn wrapping_add(&self, other: &Self, limbs: usize) -> Self {
+        self.overflowing_add(other, limbs).0
+    }
+
+    /// (self - other) mod 2^(64 limbs) and whether the difference borrowed
+    /// past 2^(64 limbs), for operands that fit in `limbs` limbs
+    pub fn overflowing_sub(&self, other: &Self, limbs: usize) -> (Self, bool) {
+        let (result, borrow) = sub_limbs(&self.limbs, &other.limbs, limbs);
+        (IntegerAU { limbs: result }.trim(), borrow)
+    }
+
+    /// (self - other) mod 2^(64 limbs)
+    pub fn wrapping_sub(&self, other: &Self, limbs: usize) -> Self {
+        self.overflowing_sub(other, limbs).0
+    }
+
+    /// (self * other) mod 2^(64 limbs), only computing the low limbs
+    pub fn wrapping_mul(&self, other: &Self, limbs: usize) -> Self {
+        let mut result = vec![0u64; limbs];
+        let mut products = 0;
+        for (i, &x) in self.limbs.iter().enumerate().take(limbs) {
+            let mut carry = 0u64;
+            for (j, &y) in other.limbs.iter().enumerate().take(limbs - i) {
+                let temp = result[i + j] as u128 + (x as u128) * (y as u128) + carry as u128;
+                result[i + j] = temp as u64;
+                carry = (temp >> 64) as u64;
+                products += 1;
+            }
+            if i + other.limbs.len() < limbs {
+                result[i + other.limbs.len()] = carry;
             }
         }
-        std::cmp::Ordering::Equal
+        op_count::record(products, products, 1);
+        IntegerAU { limbs: result }.trim()
     }
 
-    pub fn trim(mut self) -> Self {
-        // Remove leading zeros
-        while self.limbs.len() > 1 && self.limbs[self.limbs.len() - 1] == 0 {
-            self.limbs.pop();
+    /// Computes self^exp by square and multiply
+    pub fn pow(&self, exp: u32) -> Self {
+        let mut result = IntegerAU::from(1);
+        for i in (0..u32::BITS - exp.leading_zeros()).rev() {
+            result = &result * &result;
+            if (exp >> i) & 1 == 1 {
+                result *= self;
+            }
         }
-        self
+        result
+    }
+
+    /// Largest r with r^2 <= self, by Newton iteration
+    pub fn isqrt(&self) -> Self {
+        self.nth_root(2)
+    }
+
+    /// Largest r with r^k <= self, by Newton iteration
+    /// Panics if k is zero
+    pub fn nth_root(&self, k: u32) -> Self {
+        assert!(k > 0, "zeroth root is undefined");
+        if k == 1 || *self < IntegerAU::from(2) {
+            return self.clone();
+        }
+        let k_int = IntegerAU::from(k as u64);
+        let k_minus_one = IntegerAU::from(k as u64 - 1);
+        // 2^ceil(bits / k) is at least the root, and from above Newton's
+        // iterates decrease monotonically to it
+        let mut x = &IntegerAU::from(1) << self.bit_len().div_ceil(k as usize);
+        loop {
+            let y = &(&(&k_minus_one * &x) + &(self / &x.pow(k - 1))) / &k_int;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    /// Whether self is r^2 for some integer r
+    pub fn is_perfect_square(&self) -> bool {
+        // squares are 0, 1, 4 or 9 mod 16
+        if !matches!(self.limbs[0] & 15, 0 | 1 | 4 | 9) {
+            return false;
+        }
+        self.isqrt().square() == *self
+    }
+
+    /// Whether self is r^k for some integers r and k >= 2, 0 and 1 included
+    pub fn is_perfect_power(&self) -> bool {
+        if *self < IntegerAU::from(2) {
+            return true;
+        }
+        // r^(ab) = (r^a)^b so prime exponents are enough, and 2^k <= self
+        // bounds them by the bit length
+        (2..self.bit_len() as u32)
+            .filter(|&k| (2..k).take_while(|d| d * d <= k).all(|d| k % d != 0))
+            .any(|k| self.nth_root(k).pow(k) == *self)
+    }
+
+    /// Writes a * b into out, reusing out's limb buffer
+    /// Doesn't allocate when out already has room for the product
+    pub fn mul_into(a: &Self, b: &Self, out: &mut Self) {
+        let m = a.limbs.len();
+        let n = b.limbs.len();
+        let capacity = out.limbs.capacity();
+        out.limbs.clear();
+        out.limbs.resize(m + n, 0);
+
+        for (i, &x) in a.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &y) in b.limbs.iter().enumerate() {
+                let temp = out.limbs[i + j] as u128 + (x as u128) * (y as u128) + carry as u128;
+                out.limbs[i + j] = temp as u64;
+                carry = (temp >> 64) as u64;
+            }
+            out.limbs[i + n] = carry;
+        }
+
+        out.normalize();
+        op_count::record(m * n, m * n, (out.limbs.capacity() != capacity) as usize);
+    }
+
+    /// Computes self * self. Each cross product a_i a_j is computed once and
+    /// doubled, about half the limb multiplications of a general product.
+    pub fn square(&self) -> Self {
+        let n = self.limbs.len();
+        op_count::record(n * (n + 1) / 2, n * (n - 1) / 2 + 2 * n, 1);
+        let mut result = vec![0u64; 2 * n];
+
+        // Cross products a_i a_j with i < j
+        for i in 0..n {
+            let mut carry = 0u64;
+            for j in i + 1..n {
+                let temp = result[i + j] as u128
+                    + (self.limbs[i] as u128) * (self.limbs[j] as u128)
+                    + carry as u128;
+                result[i + j] = temp as u64;
+                carry = (temp >> 64) as u64;
+            }
+            result[i + n] = carry;
+        }
+
+        // Double them, the sum is below a^2 / 2 so the top bit is free
+        let mut high = 0;
+        for limb in result.iter_mut() {
+            let next = *limb >> 63;
+            *limb = (*limb << 1) | high;
+            high = next;
+        }
+
+        // Add the squares a_i^2 on the diagonal
+        let mut carry = 0u128;
+        for i in 0..n {
+            let sq = (self.limbs[i] as u128) * (self.limbs[i] as u128);
+            let low = result[2 * i] as u128 + (sq as u64) as u128 + carry;
+            result[2 * i] = low as u64;
+            let high = result[2 * i + 1] as u128 + (sq >> 64) + (low >> 64);
+            result[2 * i + 1] = high as u64;
+            carry = high >> 64;
+        }
+
+        IntegerAU { limbs: result }.trim()
+    }
+}
+
+impl Default for IntegerAU {
+    fn default() -> Self {
+        IntegerAU::from(0)
     }
 }
 
@@ -177,9 +511,612 @@ impl From<u64> for IntegerAU {
     }
 }
 
+impl TryFrom<&IntegerAU> for u64 {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(v: &IntegerAU) -> Result<u64, Self::Error> {
+        match v.limbs[..] {
+            [limb] => Ok(limb),
+            // borrow the standard error, which has no public constructor
+            _ => u64::try_from(u128::MAX),
+        }
+    }
+}
+
+impl TryFrom<&IntegerAU> for u128 {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(v: &IntegerAU) -> Result<u128, Self::Error> {
+        match v.limbs[..] {
+            [low] => Ok(low as u128),
+            [low, high] => Ok((high as u128) << 64 | low as u128),
+            _ => u128::try_from(-1i128),
+        }
+    }
+}
+
+impl Sum for IntegerAU {
+    fn sum<I: Iterator<Item = IntegerAU>>(iter: I) -> Self {
+        iter.fold(IntegerAU::from(0), |mut acc, v| {
+            acc += &v;
+            acc
+        })
+    }
+}
+
+impl<'a> Sum<&'a IntegerAU> for IntegerAU {
+    fn sum<I: Iterator<Item = &'a IntegerAU>>(iter: I) -> Self {
+        iter.fold(IntegerAU::from(0), |mut acc, v| {
+            acc += v;
+            acc
+        })
+    }
+}
+
+impl Product for IntegerAU {
+    fn product<I: Iterator<Item = IntegerAU>>(iter: I) -> Self {
+        iter.fold(IntegerAU::from(1), |mut acc, v| {
+            acc *= &v;
+            acc
+        })
+    }
+}
+
+impl<'a> Product<&'a IntegerAU> for IntegerAU {
+    fn product<I: Iterator<Item = &'a IntegerAU>>(iter: I) -> Self {
+        iter.fold(IntegerAU::from(1), |mut acc, v| {
+            acc *= v;
+            acc
+        })
+    }
+}
+
+/// Uniform sampler over a range of IntegerAU values, so that
+/// `rng.gen_range(low..high)` and `Uniform::new(low, high)` work
+#[derive(Debug, Clone)]
+pub struct UniformIntegerAU {
+    low: IntegerAU,
+    // number of values in the range
+    range: IntegerAU,
+}
+
+impl UniformSampler for UniformIntegerAU {
+    type X = IntegerAU;
+
+    fn new<B1, B2>(low: B1, high: B2) -> Self
+    where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized,
+    {
+        let (low, high) = (low.borrow(), high.borrow());
+        assert!(low < high, "Uniform::new called with `low >= high`");
+        Self {
+            low: low.clone(),
+            range: high - low,
+        }
+    }
+
+    fn new_inclusive<B1, B2>(low: B1, high: B2) -> Self
+    where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized,
+    {
+        let (low, high) = (low.borrow(), high.borrow());
+        assert!(
+            low <= high,
+            "Uniform::new_inclusive called with `low > high`"
+        );
+        Self {
+            low: low.clone(),
+            range: &(high - low) + &IntegerAU::from(1),
+        }
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+        &self.low + &IntegerAU::random_below_with(&self.range, rng)
+    }
+}
+
+impl SampleUniform for IntegerAU {
+    type Sampler = UniformIntegerAU;
+}
+
+// Below this many limbs Karatsuba falls back to schoolbook multiplication
+const KARATSUBA_LIMBS: usize = 32;
+// Below this many limbs radix conversion divides by single limbs
+const RADIX_BASE_LIMBS: usize = 32;
+// Reciprocals of moduli up to this many bits are computed by long division
+const RECIPROCAL_BASE_BITS: usize = 1024;
+// Largest power of ten that fits in a limb, and its number of zeros
+const DECIMAL_CHUNK: u64 = 10_000_000_000_000_000_000;
+const DECIMAL_CHUNK_DIGITS: usize = 19;
+
+/// Error returned when a string isn't a valid IntegerAU
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseIntegerError {
+    /// There are no digits, after the radix prefix if there is one
+    Empty,
+    /// A character that isn't a digit in the radix, at a byte offset
+    InvalidDigit {
+        digit: char,
+        index: usize,
+        radix: u32,
+    },
+}
+
+impl Display for ParseIntegerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseIntegerError::Empty => write!(f, "cannot parse integer from empty string"),
+            ParseIntegerError::InvalidDigit {
+                digit,
+                index,
+                radix,
+            } => write!(
+                f,
+                "invalid digit {digit:?} at offset {index} for radix {radix}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseIntegerError {}
+
+impl IntegerAU {
+    /// Parses digits in the given radix, without sign or prefix
+    /// Panics if radix isn't in 2..=36
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntegerError> {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix must be between 2 and 36, got {radix}"
+        );
+        if s.is_empty() {
+            return Err(ParseIntegerError::Empty);
+        }
+        let mut digits = Vec::with_capacity(s.len());
+        for (index, c) in s.char_indices() {
+            match c.to_digit(radix) {
+                Some(d) => digits.push(d as u64),
+                None => {
+                    return Err(ParseIntegerError::InvalidDigit {
+                        digit: c,
+                        index,
+                        radix,
+                    })
+                }
+            }
+        }
+
+        if radix.is_power_of_two() {
+            // Each digit lands on its own bits
+            let bits = radix.trailing_zeros() as usize;
+            let mut limbs = vec![0u64; (digits.len() * bits).div_ceil(64)];
+            for (i, &d) in digits.iter().rev().enumerate() {
+                let offset = i * bits;
+                limbs[offset / 64] |= d << (offset % 64);
+                if offset % 64 + bits > 64 {
+                    limbs[offset / 64 + 1] |= d >> (64 - offset % 64);
+                }
+            }
+            return Ok(IntegerAU { limbs }.trim());
+        }
+
+        // Feed the digits in chunks that fit in a limb
+        let chunk_len = (1..)
+            .take_while(|&n| (radix as u64).checked_pow(n).is_some())
+            .last()
+            .unwrap() as usize;
+        let mut result = IntegerAU::from(0);
+        for chunk in digits.chunks(chunk_len) {
+            let value = chunk.iter().fold(0, |acc, &d| acc * radix as u64 + d);
+            result.mul_add_small((radix as u64).pow(chunk.len() as u32), value);
+        }
+        Ok(result)
+    }
+
+    // self = self * m + a, in place
+    fn mul_add_small(&mut self, m: u64, a: u64) {
+        let mut carry = a;
+        for limb in self.limbs.iter_mut() {
+            let temp = (*limb as u128) * (m as u128) + carry as u128;
+            *limb = temp as u64;
+            carry = (temp >> 64) as u64;
+        }
+        if carry > 0 {
+            self.limbs.push(carry);
+        }
+        self.normalize();
+    }
+
+    // Divides by a single limb, returning the quotient and remainder
+    fn div_rem_small(&self, d: u64) -> (Self, u64) {
+        let mut quotient = vec![0u64; self.limbs.len()];
+        let mut rem = 0u64;
+        for (q, &limb) in quotient.iter_mut().zip(&self.limbs).rev() {
+            let cur = ((rem as u128) << 64) | limb as u128;
+            *q = (cur / d as u128) as u64;
+            rem = (cur % d as u128) as u64;
+        }
+        (IntegerAU { limbs: quotient }.trim(), rem)
+    }
+
+    // Digits of a power of two radix, most significant first
+    fn to_pow2_radix(&self, bits: usize, upper: bool) -> String {
+        let digit_count = self.bit_len().div_ceil(bits).max(1);
+        let alphabet: &[u8] = if upper {
+            b"0123456789ABCDEF"
+        } else {
+            b"0123456789abcdef"
+        };
+        (0..digit_count)
+            .rev()
+            .map(|i| {
+                let d = (0..bits).fold(0, |acc, b| acc | (self.bit(i * bits + b) as usize) << b);
+                alphabet[d] as char
+            })
+            .collect()
+    }
+
+    /// Decimal digits of the value
+    ///
+    /// Splits the number by powers 10^(19 * 2^k) and converts both halves
+    /// recursively, dividing with Barrett reduction and Karatsuba
+    /// multiplication so the conversion is subquadratic.
+    pub fn to_decimal_string(&self) -> String {
+        let mut out = String::new();
+        if self.limbs.len() <= RADIX_BASE_LIMBS {
+            write_decimal_small(self, &mut out, 0);
+            return out;
+        }
+        // powers[k] = 10^(19 * 2^k) with its Barrett reciprocal, up to the
+        // first power above self
+        let mut powers = vec![IntegerAU::from(DECIMAL_CHUNK)];
+        while powers.last().unwrap() <= self {
+            let last = powers.last().unwrap();
+            powers.push(mul_karatsuba(last, last));
+        }
+        powers.pop();
+        let powers = powers
+            .into_iter()
+            .map(|p| {
+                let mu = reciprocal(&p);
+                (p, mu)
+            })
+            .collect::<Vec<_>>();
+        write_decimal(self, &powers, &mut out, 0);
+        out
+    }
+}
+
+// a - b over `width` limbs, missing limbs read as zero and limbs beyond the
+// width are ignored, with the final borrow
+fn sub_limbs(a: &[u64], b: &[u64], width: usize) -> (Vec<u64>, bool) {
+    let mut result = Vec::with_capacity(width);
+    let mut borrow = false;
+    op_count::record(0, width, 1);
+    for i in 0..width {
+        let x = a.get(i).copied().unwrap_or(0);
+        let y = b.get(i).copied().unwrap_or(0);
+        let (diff, b1) = x.overflowing_sub(y);
+        let (diff, b2) = diff.overflowing_sub(borrow as u64);
+        result.push(diff);
+        borrow = b1 || b2;
+    }
+    (result, borrow)
+}
+
+// Appends the decimal digits of v, left padded with zeros to `pad` digits
+fn write_decimal_small(v: &IntegerAU, out: &mut String, pad: usize) {
+    let mut chunks = Vec::new();
+    let mut rest = v.clone();
+    while !rest.is_zero() {
+        let (quotient, chunk) = rest.div_rem_small(DECIMAL_CHUNK);
+        chunks.push(chunk);
+        rest = quotient;
+    }
+    let top = chunks.pop().map_or(String::new(), |c| c.to_string());
+    let len = top.len() + chunks.len() * DECIMAL_CHUNK_DIGITS;
+    if len < pad {
+        out.extend(std::iter::repeat_n('0', pad - len));
+    } else if len == 0 {
+        out.push('0');
+    }
+    out.push_str(&top);
+    for chunk in chunks.iter().rev() {
+        out.push_str(&format!("{chunk:0width$}", width = DECIMAL_CHUNK_DIGITS));
+    }
+}
+
+// Appends the digits of v < powers[k]^2 for the largest k in `powers`,
+// splitting at each power
+fn write_decimal(v: &IntegerAU, powers: &[(IntegerAU, IntegerAU)], out: &mut String, pad: usize) {
+    let Some(((power, mu), lower)) = powers.split_last() else {
+        return write_decimal_small(v, out, pad);
+    };
+    if v.limbs.len() <= RADIX_BASE_LIMBS {
+        return write_decimal_small(v, out, pad);
+    }
+    if pad == 0 && v < power {
+        return write_decimal(v, lower, out, 0);
+    }
+    let half = DECIMAL_CHUNK_DIGITS << lower.len();
+    let (quotient, remainder) = div_rem_barrett(v, power, mu);
+    write_decimal(&quotient, lower, out, pad.saturating_sub(half));
+    write_decimal(&remainder, lower, out, half);
+}
+
+// floor(2^(2s) / p) where s is the bit length of p, refined from the
+// reciprocal of the top half of p with one Newton step
+fn reciprocal(p: &IntegerAU) -> IntegerAU {
+    let s = p.bit_len();
+    let target = &IntegerAU::from(1) << (2 * s);
+    if s <= RECIPROCAL_BASE_BITS {
+        return &target / p;
+    }
+    // 64 guard bits keep the Newton step within a few units
+    let h = s / 2 + 64;
+    let top = p >> (s - h);
+    let y = &reciprocal(&top) << (s - h);
+    // y' = 2y - y^2 p / 2^(2s)
+    let t = &mul_karatsuba(&mul_karatsuba(&y, &y), p) >> (2 * s);
+    let twice = &y + &y;
+    let mut y = if t <= twice { &twice - &t } else { y };
+
+    let mut product = mul_karatsuba(&y, p);
+    while product > target {
+        y -= &IntegerAU::from(1);
+        product -= p;
+    }
+    while &product + p <= target {
+        y += &IntegerAU::from(1);
+        product += p;
+    }
+    y
+}
+
+// Quotient and remainder of x / p for x < p^2, where mu is reciprocal(p)
+fn div_rem_barrett(x: &IntegerAU, p: &IntegerAU, mu: &IntegerAU) -> (IntegerAU, IntegerAU) {
+    let s = p.bit_len();
+    let mut q = &mul_karatsuba(&(x >> (s - 1)), mu) >> (s + 1);
+    let mut r = x - &mul_karatsuba(&q, p);
+    while &r >= p {
+        r -= p;
+        q += &IntegerAU::from(1);
+    }
+    (q, r)
+}
+
+// Karatsuba multiplication, for the large operands of radix conversion
+fn mul_karatsuba(a: &IntegerAU, b: &IntegerAU) -> IntegerAU {
+    if a.limbs.len().min(b.limbs.len()) < KARATSUBA_LIMBS {
+        return a * b;
+    }
+    let half = a.limbs.len().max(b.limbs.len()) / 2;
+    let split = |v: &IntegerAU| {
+        let low = IntegerAU {
+            limbs: v.limbs[..half.min(v.limbs.len())].to_vec(),
+        }
+        .trim();
+        (low, v >> (64 * half))
+    };
+    let (a0, a1) = split(a);
+    let (b0, b1) = split(b);
+    let z0 = mul_karatsuba(&a0, &b0);
+    let z2 = mul_karatsuba(&a1, &b1);
+    let mut z1 = mul_karatsuba(&(&a0 + &a1), &(&b0 + &b1));
+    z1 -= &z0;
+    z1 -= &z2;
+    let mut result = &z2 << (128 * half);
+    result += &(&z1 << (64 * half));
+    result += &z0;
+    result
+}
+
+/// Error returned when bytes don't decode to a value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input isn't the fixed encoding length
+    Length { expected: usize, actual: usize },
+    /// The value isn't below the modulus
+    NonCanonical,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::Length { expected, actual } => {
+                write!(f, "expected {expected} bytes, got {actual}")
+            }
+            DecodeError::NonCanonical => write!(f, "value is not less than the modulus"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl IntegerAU {
+    /// Number of bytes needed to represent this number, at least one
+    pub fn byte_len(&self) -> usize {
+        self.bit_len().div_ceil(8).max(1)
+    }
+
+    /// Little-endian bytes without trailing zeros, zero is a single byte
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        let mut bytes = self
+            .limbs
+            .iter()
+            .flat_map(|limb| limb.to_le_bytes())
+            .collect::<Vec<_>>();
+        bytes.truncate(self.byte_len());
+        bytes
+    }
+
+    /// Big-endian bytes without leading zeros, zero is a single byte
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Little-endian bytes zero padded to `len`, None if the value needs
+    /// more than `len` bytes
+    pub fn to_bytes_le_padded(&self, len: usize) -> Option<Vec<u8>> {
+        let mut bytes = self.to_bytes_le();
+        if self.is_zero() {
+            bytes.clear();
+        }
+        if bytes.len() > len {
+            return None;
+        }
+        bytes.resize(len, 0);
+        Some(bytes)
+    }
+
+    /// Big-endian bytes zero padded to `len`, None if the value needs more
+    /// than `len` bytes
+    pub fn to_bytes_be_padded(&self, len: usize) -> Option<Vec<u8>> {
+        let mut bytes = self.to_bytes_le_padded(len)?;
+        bytes.reverse();
+        Some(bytes)
+    }
+
+    /// Reads little-endian bytes, an empty slice is zero
+    pub fn from_bytes_le(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .chunks(8)
+            .map(|chunk| {
+                let mut limb = [0u8; 8];
+                limb[..chunk.len()].copy_from_slice(chunk);
+                u64::from_le_bytes(limb)
+            })
+            .collect();
+        IntegerAU { limbs }.trim()
+    }
+
+    /// Reads big-endian bytes, an empty slice is zero
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        let mut bytes = bytes.to_vec();
+        bytes.reverse();
+        Self::from_bytes_le(&bytes)
+    }
+}
+
+// Hex strings in human-readable formats like JSON, little-endian bytes in
+// binary ones
+#[cfg(feature = "serde")]
+impl serde::Serialize for IntegerAU {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&format!("{self:#x}"))
+        } else {
+            serializer.serialize_bytes(&self.to_bytes_le())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for IntegerAU {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct IntegerAUVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for IntegerAUVisitor {
+            type Value = IntegerAU;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "an integer string or little-endian bytes")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<IntegerAU, E> {
+                IntegerAU::from_str(v).map_err(E::custom)
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<IntegerAU, E> {
+                Ok(IntegerAU::from_bytes_le(v))
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<IntegerAU, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.push(byte);
+                }
+                Ok(IntegerAU::from_bytes_le(&bytes))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(IntegerAUVisitor)
+        } else {
+            deserializer.deserialize_bytes(IntegerAUVisitor)
+        }
+    }
+}
+
+impl FromStr for IntegerAU {
+    type Err = ParseIntegerError;
+
+    /// Parses a decimal number, or a hexadecimal, binary or octal one with
+    /// a `0x`, `0b` or `0o` prefix
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let prefixes = [
+            ("0x", 16),
+            ("0X", 16),
+            ("0b", 2),
+            ("0B", 2),
+            ("0o", 8),
+            ("0O", 8),
+        ];
+        match prefixes
+            .iter()
+            .find_map(|&(prefix, radix)| Some((s.strip_prefix(prefix)?, radix)))
+        {
+            Some((digits, radix)) => Self::from_str_radix(digits, radix).map_err(|e| match e {
+                // offsets are into the whole string
+                ParseIntegerError::InvalidDigit {
+                    digit,
+                    index,
+                    radix,
+                } => ParseIntegerError::InvalidDigit {
+                    digit,
+                    index: index + 2,
+                    radix,
+                },
+                e => e,
+            }),
+            None => Self::from_str_radix(s, 10),
+        }
+    }
+}
+
 impl Display for IntegerAU {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_biguint())
+        f.pad_integral(true, "", &self.to_decimal_string())
+    }
+}
+
+impl LowerHex for IntegerAU {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(true, "0x", &self.to_pow2_radix(4, false))
+    }
+}
+
+impl UpperHex for IntegerAU {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(true, "0x", &self.to_pow2_radix(4, true))
+    }
+}
+
+impl Binary for IntegerAU {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(true, "0b", &self.to_pow2_radix(1, false))
+    }
+}
+
+impl Octal for IntegerAU {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(true, "0o", &self.to_pow2_radix(3, false))
     }
 }
 
@@ -192,7 +1129,7 @@ impl Rem for IntegerAU {
 }
 
 // Also implement reference version to avoid moving values
-impl<'a, 'b> Rem<&'b IntegerAU> for &'a IntegerAU {
+impl<'b> Rem<&'b IntegerAU> for &IntegerAU {
     type Output = Option<IntegerAU>;
 
     fn rem(self, other: &'b IntegerAU) -> Self::Output {
@@ -212,37 +1149,121 @@ impl PartialEq for IntegerAU {
     }
 }
 
+impl Eq for IntegerAU {}
+
 // PartialOrd requires PartialEq
 impl PartialOrd for IntegerAU {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for IntegerAU {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         // First compare lengths
         if self.limbs.len() != other.limbs.len() {
-            return Some(self.limbs.len().cmp(&other.limbs.len()));
+            return self.limbs.len().cmp(&other.limbs.len());
+        }
+
+        // Compare limbs from most significant to least significant
+        for i in (0..self.limbs.len()).rev() {
+            if self.limbs[i] != other.limbs[i] {
+                return self.limbs[i].cmp(&other.limbs[i]);
+            }
+        }
+
+        // Numbers are equal
+        std::cmp::Ordering::Equal
+    }
+}
+
+// Values are always trimmed, so equal values have equal limbs
+impl Hash for IntegerAU {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.limbs.hash(state);
+    }
+}
+
+impl AddAssign<&IntegerAU> for IntegerAU {
+    fn add_assign(&mut self, other: &IntegerAU) {
+        let max_len = std::cmp::max(self.limbs.len(), other.limbs.len());
+        let capacity = self.limbs.capacity();
+        self.limbs.resize(max_len, 0);
+        let mut carry = false;
+
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let b = other.limbs.get(i).copied().unwrap_or(0);
+            let (sum, c1) = limb.overflowing_add(b);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = c1 || c2;
+        }
+        if carry {
+            self.limbs.push(1);
+        }
+
+        op_count::record(0, max_len, (self.limbs.capacity() != capacity) as usize);
+    }
+}
+
+impl SubAssign<&IntegerAU> for IntegerAU {
+    fn sub_assign(&mut self, other: &IntegerAU) {
+        if *self < *other {
+            panic!("Subtraction underflow");
         }
+        op_count::record(0, self.limbs.len(), 0);
+        let mut borrow = false;
 
-        // Compare limbs from most significant to least significant
-        for i in (0..self.limbs.len()).rev() {
-            if self.limbs[i] != other.limbs[i] {
-                return Some(self.limbs[i].cmp(&other.limbs[i]));
-            }
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let b = other.limbs.get(i).copied().unwrap_or(0);
+            let (diff, b1) = limb.overflowing_sub(b);
+            let (diff, b2) = diff.overflowing_sub(borrow as u64);
+            *limb = diff;
+            borrow = b1 || b2;
         }
 
-        // Numbers are equal
-        Some(std::cmp::Ordering::Equal)
+        self.normalize();
     }
 }
 
-impl AddAssign<&IntegerAU> for IntegerAU {
-    fn add_assign(&mut self, other: &IntegerAU) {
-        *self = &*self + other;
+impl MulAssign<&IntegerAU> for IntegerAU {
+    // Goes through the limbs of self from the most significant one down, so
+    // the product can be accumulated over limbs that were already consumed
+    fn mul_assign(&mut self, other: &IntegerAU) {
+        let m = self.limbs.len();
+        let n = other.limbs.len();
+        let capacity = self.limbs.capacity();
+        self.limbs.resize(m + n, 0);
+
+        for i in (0..m).rev() {
+            let x = std::mem::replace(&mut self.limbs[i], 0);
+            let mut carry = 0u64;
+            for (j, &y) in other.limbs.iter().enumerate() {
+                let temp = self.limbs[i + j] as u128 + (x as u128) * (y as u128) + carry as u128;
+                self.limbs[i + j] = temp as u64;
+                carry = (temp >> 64) as u64;
+            }
+            // the limbs above hold the product of the higher limbs so far
+            let mut k = i + n;
+            while carry > 0 {
+                let (sum, overflow) = self.limbs[k].overflowing_add(carry);
+                self.limbs[k] = sum;
+                carry = overflow as u64;
+                k += 1;
+            }
+        }
+
+        self.normalize();
+        op_count::record(m * n, m * n, (self.limbs.capacity() != capacity) as usize);
     }
 }
 
-impl<'a, 'b> Add<&'b IntegerAU> for &'a IntegerAU {
+impl<'b> Add<&'b IntegerAU> for &IntegerAU {
     type Output = IntegerAU;
 
     fn add(self, other: &'b IntegerAU) -> IntegerAU {
         let max_len = std::cmp::max(self.limbs.len(), other.limbs.len());
+        op_count::record(0, max_len, 1);
         let mut result = Vec::with_capacity(max_len + 1);
         let mut carry = 0u64;
 
@@ -269,7 +1290,7 @@ impl<'a, 'b> Add<&'b IntegerAU> for &'a IntegerAU {
     }
 }
 
-impl<'a, 'b> Sub<&'b IntegerAU> for &'a IntegerAU {
+impl<'b> Sub<&'b IntegerAU> for &IntegerAU {
     type Output = IntegerAU;
 
     fn sub(self, other: &'b IntegerAU) -> IntegerAU {
@@ -279,6 +1300,7 @@ impl<'a, 'b> Sub<&'b IntegerAU> for &'a IntegerAU {
 
         let mut result = Vec::with_capacity(self.limbs.len());
         let mut borrow = false;
+        op_count::record(0, self.limbs.len(), 1);
 
         for i in 0..self.limbs.len() {
             let mut a = self.limbs[i];
@@ -305,7 +1327,7 @@ impl<'a, 'b> Sub<&'b IntegerAU> for &'a IntegerAU {
     }
 }
 
-impl<'a, 'b> Mul<&'b IntegerAU> for &'a IntegerAU {
+impl<'b> Mul<&'b IntegerAU> for &IntegerAU {
     type Output = IntegerAU;
 
     fn mul(self, other: &'b IntegerAU) -> IntegerAU {
@@ -315,6 +1337,7 @@ impl<'a, 'b> Mul<&'b IntegerAU> for &'a IntegerAU {
 
         let m = self.limbs.len();
         let n = other.limbs.len();
+        op_count::record(m * n, m * n, 1);
         let mut result = vec![0u64; m + n];
 
         for i in 0..m {
@@ -337,6 +1360,53 @@ impl<'a, 'b> Mul<&'b IntegerAU> for &'a IntegerAU {
     }
 }
 
+// Owned and mixed operands delegate to the reference implementations
+forward_owned_binop! {
+    IntegerAU;
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    BitAnd, bitand;
+    BitOr, bitor;
+    BitXor, bitxor;
+}
+
+impl AddAssign for IntegerAU {
+    fn add_assign(&mut self, other: IntegerAU) {
+        *self += &other;
+    }
+}
+
+impl SubAssign for IntegerAU {
+    fn sub_assign(&mut self, other: IntegerAU) {
+        *self -= &other;
+    }
+}
+
+impl MulAssign for IntegerAU {
+    fn mul_assign(&mut self, other: IntegerAU) {
+        *self *= &other;
+    }
+}
+
+impl BitAndAssign for IntegerAU {
+    fn bitand_assign(&mut self, other: IntegerAU) {
+        *self &= &other;
+    }
+}
+
+impl BitOrAssign for IntegerAU {
+    fn bitor_assign(&mut self, other: IntegerAU) {
+        *self |= &other;
+    }
+}
+
+impl BitXorAssign for IntegerAU {
+    fn bitxor_assign(&mut self, other: IntegerAU) {
+        *self ^= &other;
+    }
+}
+
 // Implement for owned values by delegating to reference implementation
 impl Div for IntegerAU {
     type Output = IntegerAU;
@@ -346,7 +1416,7 @@ impl Div for IntegerAU {
     }
 }
 
-impl<'a, 'b> Div<&'b IntegerAU> for &'a IntegerAU {
+impl<'b> Div<&'b IntegerAU> for &IntegerAU {
     type Output = IntegerAU;
 
     fn div(self, divisor: &'b IntegerAU) -> IntegerAU {
@@ -387,9 +1457,9 @@ impl<'a, 'b> Div<&'b IntegerAU> for &'a IntegerAU {
         for current_shift in (0..=total_shifts).rev() {
             if remainder >= shifted_divisor {
                 // Subtract shifted divisor from remainder
-                remainder = (&remainder - &shifted_divisor);
+                remainder = &remainder - &shifted_divisor;
                 // Set the corresponding bit in quotient
-                quotient = quotient | (&IntegerAU::from(1u64) << current_shift);
+                quotient.set_bit(current_shift);
             }
             shifted_divisor = &shifted_divisor >> 1;
         }
@@ -398,11 +1468,12 @@ impl<'a, 'b> Div<&'b IntegerAU> for &'a IntegerAU {
     }
 }
 
-impl<'a, 'b> BitOr<&'b IntegerAU> for &'a IntegerAU {
+impl<'b> BitOr<&'b IntegerAU> for &IntegerAU {
     type Output = IntegerAU;
 
     fn bitor(self, other: &'b IntegerAU) -> IntegerAU {
         let max_len = std::cmp::max(self.limbs.len(), other.limbs.len());
+        op_count::record(0, 0, 1);
         let mut result = Vec::with_capacity(max_len);
 
         for i in 0..max_len {
@@ -415,44 +1486,64 @@ impl<'a, 'b> BitOr<&'b IntegerAU> for &'a IntegerAU {
     }
 }
 
-// Implement for owned values
-impl BitOr for IntegerAU {
+// Bitwise AND
+impl<'b> BitAnd<&'b IntegerAU> for &IntegerAU {
     type Output = IntegerAU;
 
-    fn bitor(self, other: IntegerAU) -> IntegerAU {
-        &self | &other
-    }
-}
+    fn bitand(self, other: &'b IntegerAU) -> IntegerAU {
+        let min_len = std::cmp::min(self.limbs.len(), other.limbs.len());
+        op_count::record(0, 0, 1);
+        let mut result = Vec::with_capacity(min_len);
 
-impl<'a> BitOr<IntegerAU> for &'a IntegerAU {
-    type Output = IntegerAU;
+        for i in 0..min_len {
+            result.push(self.limbs[i] & other.limbs[i]);
+        }
 
-    fn bitor(self, other: IntegerAU) -> IntegerAU {
-        self | &other
+        IntegerAU { limbs: result }.trim()
     }
 }
 
-impl<'a> BitOr<&'a IntegerAU> for IntegerAU {
-    type Output = IntegerAU;
+impl BitAndAssign<&IntegerAU> for IntegerAU {
+    fn bitand_assign(&mut self, other: &IntegerAU) {
+        self.limbs.truncate(other.limbs.len());
+        for (a, b) in self.limbs.iter_mut().zip(&other.limbs) {
+            *a &= b;
+        }
+        self.normalize();
+    }
+}
 
-    fn bitor(self, other: &'a IntegerAU) -> IntegerAU {
-        &self | other
+impl BitOrAssign<&IntegerAU> for IntegerAU {
+    fn bitor_assign(&mut self, other: &IntegerAU) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+        for (a, b) in self.limbs.iter_mut().zip(&other.limbs) {
+            *a |= b;
+        }
     }
 }
 
-// Bitwise AND
-impl<'a, 'b> BitAnd<&'b IntegerAU> for &'a IntegerAU {
+// Bitwise XOR
+impl<'b> BitXor<&'b IntegerAU> for &IntegerAU {
     type Output = IntegerAU;
 
-    fn bitand(self, other: &'b IntegerAU) -> IntegerAU {
-        let min_len = std::cmp::min(self.limbs.len(), other.limbs.len());
-        let mut result = Vec::with_capacity(min_len);
+    fn bitxor(self, other: &'b IntegerAU) -> IntegerAU {
+        let mut result = self.clone();
+        result ^= other;
+        result
+    }
+}
 
-        for i in 0..min_len {
-            result.push(self.limbs[i] & other.limbs[i]);
+impl BitXorAssign<&IntegerAU> for IntegerAU {
+    fn bitxor_assign(&mut self, other: &IntegerAU) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
         }
-
-        IntegerAU { limbs: result }.trim()
+        for (a, b) in self.limbs.iter_mut().zip(&other.limbs) {
+            *a ^= b;
+        }
+        self.normalize();
     }
 }
 
@@ -465,11 +1556,12 @@ impl Shl<usize> for IntegerAU {
 }
 
 // Left shift
-impl<'a> Shl<usize> for &'a IntegerAU {
+impl Shl<usize> for &IntegerAU {
     type Output = IntegerAU;
 
     fn shl(self, shift: usize) -> IntegerAU {
         // Handle zero case
+        op_count::record(0, 0, 1);
         if self.limbs.len() == 1 && self.limbs[0] == 0 {
             return IntegerAU { limbs: vec![0] };
         }
@@ -481,9 +1573,7 @@ impl<'a> Shl<usize> for &'a IntegerAU {
         let mut result = vec![0u64; self.limbs.len() + word_shifts + 1];
 
         // Copy original number shifted by words
-        for i in 0..self.limbs.len() {
-            result[i + word_shifts] = self.limbs[i];
-        }
+        result[word_shifts..word_shifts + self.limbs.len()].copy_from_slice(&self.limbs);
 
         // Handle bit shifts
         if bit_shifts > 0 {
@@ -513,11 +1603,12 @@ impl Shr<usize> for IntegerAU {
 }
 
 // Right shift
-impl<'a> Shr<usize> for &'a IntegerAU {
+impl Shr<usize> for &IntegerAU {
     type Output = IntegerAU;
 
     fn shr(self, shift: usize) -> IntegerAU {
         // Handle zero case
+        op_count::record(0, 0, 1);
         if self.limbs.len() == 1 && self.limbs[0] == 0 {
             return IntegerAU { limbs: vec![0] };
         }
@@ -551,9 +1642,183 @@ impl<'a> Shr<usize> for &'a IntegerAU {
     }
 }
 
+impl ShrAssign<usize> for IntegerAU {
+    fn shr_assign(&mut self, shift: usize) {
+        let word_shifts = shift / 64;
+        let bit_shifts = shift % 64;
+
+        if word_shifts >= self.limbs.len() {
+            self.limbs.clear();
+            self.limbs.push(0);
+            return;
+        }
+
+        // Shift whole words down, then the bits within words
+        self.limbs.drain(..word_shifts);
+        if bit_shifts > 0 {
+            for i in 0..self.limbs.len() {
+                let high = self
+                    .limbs
+                    .get(i + 1)
+                    .map_or(0, |&next| next << (64 - bit_shifts));
+                self.limbs[i] = (self.limbs[i] >> bit_shifts) | high;
+            }
+        }
+
+        self.normalize();
+    }
+}
+
+impl ShlAssign<usize> for IntegerAU {
+    fn shl_assign(&mut self, shift: usize) {
+        if self.is_zero() {
+            return;
+        }
+        let word_shifts = shift / 64;
+        let bit_shifts = shift % 64;
+
+        // Shift the bits within words, then insert whole words below
+        if bit_shifts > 0 {
+            let top = self.limbs.last().unwrap() >> (64 - bit_shifts);
+            for i in (0..self.limbs.len()).rev() {
+                let low = if i > 0 {
+                    self.limbs[i - 1] >> (64 - bit_shifts)
+                } else {
+                    0
+                };
+                self.limbs[i] = (self.limbs[i] << bit_shifts) | low;
+            }
+            if top != 0 {
+                self.limbs.push(top);
+            }
+        }
+        self.limbs.splice(..0, std::iter::repeat_n(0, word_shifts));
+    }
+}
+
+#[cfg(feature = "num-traits")]
+mod num_traits_impls {
+    use num_traits::CheckedAdd;
+    use num_traits::CheckedSub;
+    use num_traits::FromPrimitive;
+    use num_traits::Num;
+    use num_traits::One;
+    use num_traits::Pow;
+    use num_traits::ToPrimitive;
+    use num_traits::Unsigned;
+    use num_traits::Zero;
+
+    use super::IntegerAU;
+    use super::ParseIntegerError;
+
+    impl Zero for IntegerAU {
+        fn zero() -> Self {
+            IntegerAU::from(0)
+        }
+
+        fn is_zero(&self) -> bool {
+            IntegerAU::is_zero(self)
+        }
+    }
+
+    impl One for IntegerAU {
+        fn one() -> Self {
+            IntegerAU::from(1)
+        }
+    }
+
+    impl Num for IntegerAU {
+        type FromStrRadixErr = ParseIntegerError;
+
+        fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntegerError> {
+            IntegerAU::from_str_radix(s, radix)
+        }
+    }
+
+    impl Unsigned for IntegerAU {}
+
+    impl CheckedAdd for IntegerAU {
+        fn checked_add(&self, v: &Self) -> Option<Self> {
+            Some(self + v)
+        }
+    }
+
+    impl CheckedSub for IntegerAU {
+        fn checked_sub(&self, v: &Self) -> Option<Self> {
+            IntegerAU::checked_sub(self, v)
+        }
+    }
+
+    impl Pow<u32> for IntegerAU {
+        type Output = IntegerAU;
+
+        fn pow(self, exp: u32) -> IntegerAU {
+            IntegerAU::pow(&self, exp)
+        }
+    }
+
+    impl Pow<u32> for &IntegerAU {
+        type Output = IntegerAU;
+
+        fn pow(self, exp: u32) -> IntegerAU {
+            IntegerAU::pow(self, exp)
+        }
+    }
+
+    impl ToPrimitive for IntegerAU {
+        fn to_i64(&self) -> Option<i64> {
+            self.to_u64().and_then(|v| i64::try_from(v).ok())
+        }
+
+        fn to_u64(&self) -> Option<u64> {
+            u64::try_from(self).ok()
+        }
+
+        fn to_i128(&self) -> Option<i128> {
+            self.to_u128().and_then(|v| i128::try_from(v).ok())
+        }
+
+        fn to_u128(&self) -> Option<u128> {
+            u128::try_from(self).ok()
+        }
+
+        fn to_f64(&self) -> Option<f64> {
+            // the top 64 bits are enough for the 53 bit mantissa, up to
+            // rounding of the discarded bits
+            let shift = self.bit_len().saturating_sub(64);
+            let top = u64::try_from(&(self >> shift)).ok()? as f64;
+            Some(top * 2f64.powi(shift as i32))
+        }
+    }
+
+    impl FromPrimitive for IntegerAU {
+        fn from_i64(n: i64) -> Option<Self> {
+            u64::try_from(n).ok().map(IntegerAU::from)
+        }
+
+        fn from_u64(n: u64) -> Option<Self> {
+            Some(IntegerAU::from(n))
+        }
+
+        fn from_u128(n: u128) -> Option<Self> {
+            Some(
+                IntegerAU {
+                    limbs: vec![n as u64, (n >> 64) as u64],
+                }
+                .trim(),
+            )
+        }
+
+        fn from_i128(n: i128) -> Option<Self> {
+            u128::try_from(n).ok().and_then(Self::from_u128)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::seed::test_rng;
     use num_bigint::BigUint;
     use rand::Rng;
     use std::str::FromStr;
@@ -617,7 +1882,7 @@ mod tests {
 
                 let a = IntegerAU::from_biguint(a_big);
                 let b = IntegerAU::from_biguint(b_big);
-                let result = (&a - &b);
+                let result = &a - &b;
 
                 assert_eq!(
                     result.to_biguint(),
@@ -745,7 +2010,7 @@ mod tests {
             );
         }
         // Random test cases
-        let mut rng = rand::thread_rng();
+        let mut rng = test_rng();
 
         for _ in 0..1000 {
             // Generate random number of limbs (1-4)
@@ -834,6 +2099,502 @@ mod tests {
                 a_str,
                 b_str
             );
+
+            // Test XOR
+            assert_eq!(
+                (&a ^ &b).to_biguint(),
+                &a_big ^ &b_big,
+                "Failed XOR test: {} ^ {}",
+                a_str,
+                b_str
+            );
+            assert_eq!((a.clone() ^ b.clone()).limbs, (&a ^ &b).limbs);
+            assert_eq!((a.clone() & b.clone()).limbs, (&a & &b).limbs);
+        }
+        // equal high limbs cancel
+        let a = &IntegerAU::from(1) << 130;
+        assert_eq!((&a ^ &a).limbs, vec![0]);
+    }
+
+    #[test]
+    fn test_bit_access() {
+        let mut rng = test_rng();
+        for _ in 0..100 {
+            let a = IntegerAU::random_below_with(&(&IntegerAU::from(1) << 300), &mut rng);
+            let a_big = a.to_biguint();
+            let i = rng.gen_range(0..320);
+
+            let mut v = a.clone();
+            v.set_bit(i);
+            let mut expected = a_big.clone();
+            expected.set_bit(i as u64, true);
+            assert_eq!(v.to_biguint(), expected, "Failed set_bit test: {a}, {i}");
+            v.clear_bit(i);
+            expected.set_bit(i as u64, false);
+            assert_eq!(v.to_biguint(), expected, "Failed clear_bit test: {a}, {i}");
+            assert_eq!(v, IntegerAU::from_biguint(expected));
+
+            assert_eq!(a.count_ones() as u64, a_big.count_ones());
+            assert_eq!(a.trailing_zeros().map(|z| z as u64), a_big.trailing_zeros());
+            assert_eq!(a.leading_zeros(320), 320 - a.bit_len());
+
+            let not = a.not(320);
+            assert_eq!(
+                not.to_biguint(),
+                ((BigUint::from(1u64) << 320u32) - 1u64) ^ &a_big,
+                "Failed not test: {a}"
+            );
+            assert_eq!(not.not(320), a);
+
+            let width = rng.gen_range(1..=64);
+            let expected = (&a_big >> i) & ((BigUint::from(1u64) << width) - 1u64);
+            assert_eq!(
+                BigUint::from(a.bits_at(i, width)),
+                expected,
+                "Failed bits_at test: {a}, {i}, {width}"
+            );
+
+            let bits = a.bits().collect::<Vec<_>>();
+            assert_eq!(bits.len(), a.bit_len());
+            assert!(bits.iter().enumerate().all(|(j, &b)| b == a.bit(j)));
+            // the windows are the base 2^width digits
+            let digits = a
+                .windows(width)
+                .rev()
+                .fold(BigUint::from(0u64), |acc, d| (acc << width) + d);
+            assert_eq!(digits, a_big, "Failed windows test: {a}, {width}");
+        }
+
+        let zero = IntegerAU::from(0);
+        assert_eq!(zero.trailing_zeros(), None);
+        assert_eq!(zero.bits().count(), 0);
+        assert_eq!(zero.windows(4).count(), 0);
+        assert_eq!(zero.not(0), zero);
+        assert_eq!(zero.not(64), IntegerAU::from(u64::MAX));
+        let mut v = IntegerAU::from(1);
+        v.clear_bit(0);
+        v.clear_bit(500);
+        assert_eq!(v.limbs, vec![0]);
+        assert_eq!(IntegerAU::from(5).leading_zeros(2), 0);
+        assert_eq!(IntegerAU::from(u64::MAX).bits_at(0, 64), u64::MAX);
+        assert_eq!((&IntegerAU::from(1) << 64).trailing_zeros(), Some(64));
+    }
+
+    #[test]
+    fn test_square() {
+        let mut rng = test_rng();
+        for _ in 0..200 {
+            let bits = rng.gen_range(1..600);
+            let a = IntegerAU::random_below_with(&(&IntegerAU::from(1) << bits), &mut rng);
+            assert_eq!(a.square(), &a * &a, "Failed square test: {a}");
+        }
+        // all ones limbs produce the largest carries
+        for n in 1..6 {
+            let a = IntegerAU {
+                limbs: vec![u64::MAX; n],
+            };
+            assert_eq!(a.square().to_biguint(), a.to_biguint().pow(2));
+        }
+        assert_eq!(IntegerAU::from(0).square().limbs, vec![0]);
+        assert_eq!(IntegerAU::from(1).square().limbs, vec![1]);
+    }
+
+    #[test]
+    fn test_roots() {
+        let mut rng = test_rng();
+        for _ in 0..100 {
+            let bits = rng.gen_range(1..300);
+            let a = IntegerAU::random_below_with(&(&IntegerAU::from(1) << bits), &mut rng);
+            let a_big = a.to_biguint();
+            assert_eq!(
+                a.isqrt().to_biguint(),
+                a_big.sqrt(),
+                "Failed isqrt test: {a}"
+            );
+            let k = rng.gen_range(1..10);
+            assert_eq!(
+                a.nth_root(k).to_biguint(),
+                a_big.nth_root(k),
+                "Failed nth_root test: {a}, {k}"
+            );
+            assert_eq!(a.is_perfect_square(), a_big.sqrt().pow(2) == a_big);
+
+            // exact powers and their neighbours
+            let square = a.square();
+            assert!(square.is_perfect_square(), "Failed square test: {a}");
+            assert_eq!(square.isqrt(), a);
+            let cube = a.pow(3);
+            assert_eq!(cube.nth_root(3), a);
+            assert!(cube.is_perfect_power(), "Failed perfect power test: {a}^3");
+            if a > IntegerAU::from(1) {
+                assert!(!(&square + &IntegerAU::from(1)).is_perfect_square());
+                assert_eq!(
+                    (&square - &IntegerAU::from(1)).isqrt(),
+                    &a - &IntegerAU::from(1)
+                );
+                assert_eq!(
+                    (&cube - &IntegerAU::from(1)).nth_root(3),
+                    &a - &IntegerAU::from(1)
+                );
+            }
+        }
+
+        assert_eq!(IntegerAU::from(0).isqrt(), IntegerAU::from(0));
+        assert_eq!(IntegerAU::from(1).nth_root(5), IntegerAU::from(1));
+        assert!(IntegerAU::from(0).is_perfect_square());
+        assert!(IntegerAU::from(1).is_perfect_power());
+        assert!((&IntegerAU::from(1) << 127).is_perfect_power());
+        assert!(IntegerAU::from(7).pow(11).is_perfect_power());
+        assert!(!IntegerAU::from(2).is_perfect_power());
+        assert!(!IntegerAU::from(72).is_perfect_power());
+        // 2^255 - 19 is prime
+        let p = &(&IntegerAU::from(1) << 255) - &IntegerAU::from(19);
+        assert!(!p.is_perfect_power());
+    }
+
+    #[test]
+    fn test_assign_operations() {
+        let mut rng = test_rng();
+        for _ in 0..200 {
+            let bits_a = rng.gen_range(1..400);
+            let bits_b = rng.gen_range(1..400);
+            let a = IntegerAU::random_below_with(&(&IntegerAU::from(1) << bits_a), &mut rng);
+            // non-zero so that a * b + b - a can't underflow
+            let b = &IntegerAU::random_below_with(&(&IntegerAU::from(1) << bits_b), &mut rng)
+                + &IntegerAU::from(1);
+            let shift = rng.gen_range(0..450);
+
+            let mut v = a.clone();
+            v *= &b;
+            assert_eq!(v, &a * &b, "Failed *= test: {a} * {b}");
+            let mut out = IntegerAU::from(u64::MAX);
+            IntegerAU::mul_into(&a, &b, &mut out);
+            assert_eq!(out, &a * &b, "Failed mul_into test: {a} * {b}");
+            v += &b;
+            assert_eq!(v, &(&a * &b) + &b, "Failed += test");
+            v -= &a;
+            assert_eq!(v, &(&(&a * &b) + &b) - &a, "Failed -= test");
+            let mut v = a.clone();
+            v &= &b;
+            assert_eq!(v, &a & &b, "Failed &= test: {a} & {b}");
+            let mut v = a.clone();
+            v >>= shift;
+            assert_eq!(v, &a >> shift, "Failed >>= test: {a} >> {shift}");
+            let mut v = a.clone();
+            v <<= shift;
+            assert_eq!(v, &a << shift, "Failed <<= test: {a} << {shift}");
+            let mut v = a.clone();
+            v |= &b;
+            assert_eq!(v, &a | &b, "Failed |= test: {a} | {b}");
+            v ^= b.clone();
+            assert_eq!(v, &(&a | &b) ^ &b, "Failed ^= test: {a} ^ {b}");
+        }
+        let mut zero = IntegerAU::from(5);
+        zero -= &IntegerAU::from(5);
+        assert_eq!(zero.limbs, vec![0]);
+        zero *= &IntegerAU::from(7);
+        assert_eq!(zero.limbs, vec![0]);
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let mut rng = test_rng();
+        for _ in 0..200 {
+            let a = IntegerAU::random_below_with(&(&IntegerAU::from(1) << 300), &mut rng);
+            let b = IntegerAU::random_below_with(&(&IntegerAU::from(1) << 300), &mut rng);
+            let (a_big, b_big) = (a.to_biguint(), b.to_biguint());
+            assert_eq!(a.checked_sub(&b).is_some(), a >= b);
+            if a < b {
+                assert_eq!(a.saturating_sub(&b), IntegerAU::from(0));
+            } else {
+                assert_eq!(a.saturating_sub(&b), &a - &b);
+            }
+            assert_eq!(a.abs_diff(&b), b.abs_diff(&a));
+            let expected = if a_big >= b_big {
+                &a_big - &b_big
+            } else {
+                &b_big - &a_big
+            };
+            assert_eq!(a.abs_diff(&b).to_biguint(), expected);
+
+            for limbs in [1, 2, 5] {
+                let modulus = BigUint::from(1u64) << (64 * limbs);
+                let (sum, carry) = a.overflowing_add(&b, limbs);
+                let a_low = &a_big % &modulus;
+                let b_low = &b_big % &modulus;
+                assert_eq!(sum.to_biguint(), (&a_low + &b_low) % &modulus);
+                assert_eq!(carry, &a_low + &b_low >= modulus);
+                assert_eq!(a.wrapping_add(&b, limbs), sum);
+                let (difference, borrow) = a.overflowing_sub(&b, limbs);
+                assert_eq!(
+                    difference.to_biguint(),
+                    (&a_low + &modulus - &b_low) % &modulus
+                );
+                assert_eq!(borrow, a_low < b_low);
+                assert_eq!(a.wrapping_sub(&b, limbs), difference);
+                assert_eq!(
+                    a.wrapping_mul(&b, limbs).to_biguint(),
+                    (&a_big * &b_big) % &modulus
+                );
+            }
+        }
+        let zero = IntegerAU::from(0);
+        let one = IntegerAU::from(1);
+        assert_eq!(zero.checked_sub(&one), None);
+        assert_eq!(
+            zero.overflowing_sub(&one, 1),
+            (IntegerAU::from(u64::MAX), true)
+        );
+        assert_eq!(one.checked_sub(&one), Some(zero.clone()));
+        assert_eq!(
+            IntegerAU::from(u64::MAX).overflowing_add(&one, 1),
+            (zero.clone(), true)
+        );
+        assert_eq!(one.wrapping_mul(&one, 0), zero);
+    }
+
+    #[test]
+    #[should_panic(expected = "Subtraction underflow")]
+    fn test_sub_assign_underflow() {
+        let mut v = IntegerAU::from(1);
+        v -= &IntegerAU::from(2);
+    }
+
+    #[test]
+    fn test_parse() {
+        let cases = [
+            ("0", "0"),
+            ("18446744073709551616", "18446744073709551616"),
+            ("0xffffffff00000001", "18446744069414584321"),
+            ("0XFFFFFFFF00000001", "18446744069414584321"),
+            ("0b101", "5"),
+            ("0o777", "511"),
+            ("000123", "123"),
+            ("0x0", "0"),
+            (
+                "0o1234567012345670123456701234567",
+                "1616895878810725189668911479",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                IntegerAU::from_str(input).unwrap().to_biguint(),
+                BigUint::from_str(expected).unwrap(),
+                "Failed parse test: {input}"
+            );
+        }
+        assert_eq!(
+            IntegerAU::from_str_radix("zz", 36).unwrap(),
+            IntegerAU::from(35 * 36 + 35)
+        );
+        assert_eq!(IntegerAU::from_str(""), Err(ParseIntegerError::Empty));
+        assert_eq!(IntegerAU::from_str("0x"), Err(ParseIntegerError::Empty));
+        assert_eq!(
+            IntegerAU::from_str("12a"),
+            Err(ParseIntegerError::InvalidDigit {
+                digit: 'a',
+                index: 2,
+                radix: 10
+            })
+        );
+        assert_eq!(
+            IntegerAU::from_str("0b102"),
+            Err(ParseIntegerError::InvalidDigit {
+                digit: '2',
+                index: 4,
+                radix: 2
+            })
+        );
+        assert!(IntegerAU::from_str("-1").is_err());
+        assert!(IntegerAU::from_str(" 1").is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        let v = IntegerAU::from_str("0x1fffffffffffffffe").unwrap();
+        assert_eq!(format!("{v:x}"), "1fffffffffffffffe");
+        assert_eq!(format!("{v:#X}"), "0x1FFFFFFFFFFFFFFFE");
+        assert_eq!(format!("{:b}", IntegerAU::from(10)), "1010");
+        assert_eq!(format!("{:#o}", IntegerAU::from(8)), "0o10");
+        assert_eq!(format!("{:x}", IntegerAU::from(0)), "0");
+        assert_eq!(format!("{}", IntegerAU::from(0)), "0");
+        assert_eq!(format!("{:>6}", IntegerAU::from(42)), "    42");
+        assert_eq!(format!("{:#010x}", IntegerAU::from(255)), "0x000000ff");
+    }
+
+    #[test]
+    fn test_radix_roundtrip() {
+        let mut rng = test_rng();
+        // sizes on both sides of the divide and conquer thresholds
+        for bits in [1, 63, 64, 65, 1000, 2048, 2049, 5000, 20000, 70000] {
+            let v = IntegerAU::random_below_with(&(&IntegerAU::from(1) << bits), &mut rng);
+            let decimal = v.to_string();
+            assert_eq!(decimal, v.to_biguint().to_string(), "Failed display test");
+            assert_eq!(IntegerAU::from_str(&decimal).unwrap(), v);
+            assert_eq!(format!("{v:x}"), format!("{:x}", v.to_biguint()));
+            assert_eq!(format!("{v:o}"), format!("{:o}", v.to_biguint()));
+            assert_eq!(IntegerAU::from_str(&format!("{v:#x}")).unwrap(), v);
+            assert_eq!(IntegerAU::from_str(&format!("{v:#o}")).unwrap(), v);
+            assert_eq!(IntegerAU::from_str(&format!("{v:#b}")).unwrap(), v);
+        }
+        // powers of ten and their neighbours hit the split points exactly
+        let mut power = IntegerAU::from(1);
+        for _ in 0..800 {
+            power *= &IntegerAU::from(10);
+        }
+        let below = &power - &IntegerAU::from(1);
+        assert_eq!(power.to_string(), format!("1{}", "0".repeat(800)));
+        assert_eq!(below.to_string(), "9".repeat(800));
+    }
+
+    #[test]
+    fn test_bytes() {
+        let mut rng = test_rng();
+        for bits in [1, 8, 9, 63, 64, 65, 255, 256, 1000] {
+            let v = IntegerAU::random_below_with(&(&IntegerAU::from(1) << bits), &mut rng);
+            let big = v.to_biguint();
+            assert_eq!(v.to_bytes_le(), big.to_bytes_le());
+            assert_eq!(v.to_bytes_be(), big.to_bytes_be());
+            assert_eq!(IntegerAU::from_bytes_le(&v.to_bytes_le()), v);
+            assert_eq!(IntegerAU::from_bytes_be(&v.to_bytes_be()), v);
+            assert_eq!(v.byte_len(), v.to_bytes_le().len());
+        }
+        let v = IntegerAU::from(0x0102);
+        assert_eq!(v.to_bytes_le_padded(4), Some(vec![2, 1, 0, 0]));
+        assert_eq!(v.to_bytes_be_padded(4), Some(vec![0, 0, 1, 2]));
+        assert_eq!(v.to_bytes_be_padded(2), Some(vec![1, 2]));
+        assert_eq!(v.to_bytes_le_padded(1), None);
+        assert_eq!(IntegerAU::from(0).to_bytes_le(), vec![0]);
+        assert_eq!(IntegerAU::from(0).to_bytes_be_padded(0), Some(vec![]));
+        assert_eq!(IntegerAU::from_bytes_be(&[]), IntegerAU::from(0));
+        // leading zero bytes are ignored
+        assert_eq!(IntegerAU::from_bytes_be(&[0, 0, 1, 2]), v);
+        assert_eq!(
+            IntegerAU::from_bytes_le(&[2, 1, 0, 0, 0, 0, 0, 0, 0, 0]).limbs,
+            vec![0x0102]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        let v = IntegerAU::from_str("0x1fffffffffffffffe").unwrap();
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(json, "\"0x1fffffffffffffffe\"");
+        assert_eq!(serde_json::from_str::<IntegerAU>(&json).unwrap(), v);
+        let decimal = serde_json::from_str::<IntegerAU>("\"36893488147419103230\"").unwrap();
+        assert_eq!(decimal, v);
+        assert!(serde_json::from_str::<IntegerAU>("\"0xg\"").is_err());
+        let bytes = bincode::serialize(&v).unwrap();
+        assert_eq!(bincode::deserialize::<IntegerAU>(&bytes).unwrap(), v);
+        let zero = bincode::serialize(&IntegerAU::from(0)).unwrap();
+        assert_eq!(
+            bincode::deserialize::<IntegerAU>(&zero).unwrap(),
+            IntegerAU::from(0)
+        );
+    }
+
+    #[test]
+    fn test_std_traits() {
+        use std::collections::BTreeSet;
+        use std::collections::HashSet;
+
+        let mut rng = test_rng();
+        let bound = &IntegerAU::from(1) << 200;
+        let mut values = (0..50)
+            .map(|_| IntegerAU::random_below_with(&bound, &mut rng))
+            .collect::<Vec<_>>();
+        values.push(IntegerAU::default());
+        values.push(values[3].clone());
+
+        let mut expected = values.iter().map(IntegerAU::to_biguint).collect::<Vec<_>>();
+        expected.sort();
+        values.sort();
+        assert_eq!(
+            values.iter().map(IntegerAU::to_biguint).collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(values[0], IntegerAU::from(0));
+        assert_eq!(values.iter().max(), values.last());
+
+        let hashed = values.iter().collect::<HashSet<_>>();
+        let ordered = values.iter().collect::<BTreeSet<_>>();
+        assert_eq!(hashed.len(), values.len() - 1);
+        assert_eq!(ordered.len(), values.len() - 1);
+
+        let sum = values.iter().sum::<IntegerAU>();
+        assert_eq!(sum.to_biguint(), expected.iter().sum());
+        let product = values[1..4].iter().cloned().product::<IntegerAU>();
+        assert_eq!(product.to_biguint(), expected[1..4].iter().product());
+
+        let (a, b) = (values[5].clone(), values[4].clone());
+        assert_eq!(a.clone() + b.clone(), &a + &b);
+        assert_eq!(a.clone() - &b, &a - &b);
+        assert_eq!(&a * b.clone(), &a * &b);
+        assert_eq!(a.clone() & b.clone(), &a & &b);
+        assert_eq!(IntegerAU::from(3).pow(5), IntegerAU::from(243));
+        assert_eq!(a.pow(3), &(&a * &a) * &a);
+        assert_eq!(a.pow(0), IntegerAU::from(1));
+
+        assert_eq!(u64::try_from(&IntegerAU::from(7)), Ok(7));
+        assert!(u64::try_from(&bound).is_err());
+        assert_eq!(
+            u128::try_from(&(&IntegerAU::from(1) << 100)),
+            Ok(1u128 << 100)
+        );
+        assert!(u128::try_from(&bound).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "num-traits")]
+    fn test_num_traits() {
+        use num_traits::CheckedSub;
+        use num_traits::FromPrimitive;
+        use num_traits::Num;
+        use num_traits::One;
+        use num_traits::Pow;
+        use num_traits::ToPrimitive;
+        use num_traits::Zero;
+
+        // generic numeric code over any unsigned Num
+        fn triangular<T: Num + Clone + FromPrimitive>(n: u64) -> T {
+            (1..=n).fold(T::zero(), |acc, i| acc + T::from_u64(i).unwrap())
+        }
+        assert_eq!(triangular::<IntegerAU>(100), IntegerAU::from(5050));
+
+        assert!(IntegerAU::zero().is_zero());
+        assert_eq!(IntegerAU::one(), IntegerAU::from(1));
+        assert_eq!(
+            <IntegerAU as Num>::from_str_radix("ff", 16),
+            Ok(IntegerAU::from(255))
+        );
+        assert_eq!(
+            CheckedSub::checked_sub(&IntegerAU::from(2), &IntegerAU::from(3)),
+            None
+        );
+        assert_eq!(
+            Pow::pow(IntegerAU::from(2), 100u32),
+            &IntegerAU::from(1) << 100
+        );
+        assert_eq!(IntegerAU::from_i64(-1), None);
+        assert_eq!(
+            IntegerAU::from_u128(u128::MAX).unwrap().to_u128(),
+            Some(u128::MAX)
+        );
+        assert_eq!(IntegerAU::from(u64::MAX).to_i64(), None);
+        assert_eq!((&IntegerAU::from(1) << 70).to_f64(), Some(2f64.powi(70)));
+        assert_eq!(IntegerAU::from(12345).to_f64(), Some(12345.0));
+    }
+
+    #[test]
+    fn test_karatsuba() {
+        let mut rng = test_rng();
+        for _ in 0..20 {
+            let a_bits = rng.gen_range(1..8000);
+            let b_bits = rng.gen_range(1..8000);
+            let a = IntegerAU::random_below_with(&(&IntegerAU::from(1) << a_bits), &mut rng);
+            let b = IntegerAU::random_below_with(&(&IntegerAU::from(1) << b_bits), &mut rng);
+            assert_eq!(mul_karatsuba(&a, &b), &a * &b);
         }
     }
 
@@ -922,7 +2683,7 @@ mod tests {
 
     #[test]
     fn test_random_bit_len() {
-        let mut rng = rand::thread_rng();
+        let mut rng = test_rng();
 
         for _ in 0..1000 {
             // Generate 1-4 random limbs
@@ -1001,7 +2762,7 @@ mod tests {
     fn test_division_by_zero() {
         let a = IntegerAU::from_biguint(BigUint::from(42u64));
         let zero = IntegerAU::from_biguint(BigUint::from(0u64));
-        &a / &zero;
+        let _ = &a / &zero;
     }
 
     #[test]
@@ -1034,7 +2795,7 @@ mod tests {
     #[test]
     fn test_random_division() {
         use rand::Rng;
-        let mut rng = rand::thread_rng();
+        let mut rng = test_rng();
 
         for _ in 0..100 {
             // Generate random numbers (making sure divisor isn't zero)
@@ -0,0 +1,545 @@
+use super::ct;
+use super::integer_au::IntegerAU;
+use super::reducer::Reducer;
+
+// Number of divsteps batched into one transition matrix by safegcd
+const SAFEGCD_BATCH: u32 = 62;
+// Number of inner iterations run on the approximations by bingcd
+const BINGCD_BATCH: u32 = 31;
+// Largest modulus supported by bingcd
+pub const BINGCD_MAX_BITS: usize = 256;
+
+/// Inverts a value using Fermat's little theorem, a^(p - 2) = a^-1 mod p
+/// The value and result are in the reducer's representation.
+/// Returns None for zero.
+pub fn invert_fermat<R: Reducer + ?Sized>(reducer: &R, a: &IntegerAU) -> Option<IntegerAU> {
+    if a.is_zero() {
+        return None;
+    }
+    let exp = reducer.modulus() - &IntegerAU::from(2);
+    Some(reducer.pow(a, &exp))
+}
+
+/// Inverts every value in place using Montgomery's trick
+/// The running products of the values are accumulated, the final product is
+/// inverted once and the individual inverses are recovered walking back
+/// through the products. This costs one inversion and 3n multiplications
+/// for n non-zero values instead of n inversions.
+/// Zeros have no inverse and are left as zero.
+pub fn batch_invert<R: Reducer + ?Sized>(reducer: &R, values: &mut [IntegerAU]) {
+    // prefix[i] is the product of the non-zero values before index i
+    let mut prefix = Vec::with_capacity(values.len());
+    let mut acc = reducer.one();
+    for v in values.iter() {
+        prefix.push(acc.clone());
+        if !v.is_zero() {
+            acc = reducer.mul(&acc, v);
+        }
+    }
+
+    // a product of non-zero values mod a prime is never zero
+    let mut inv = reducer
+        .invert(&acc)
+        .expect("product of non-zero values is invertible");
+    for (v, before) in values.iter_mut().zip(prefix).rev() {
+        if v.is_zero() {
+            continue;
+        }
+        let v_inv = reducer.mul(&inv, &before);
+        inv = reducer.mul(&inv, v);
+        *v = v_inv;
+    }
+}
+
+/// Inverts `a` modulo an odd modulus using the Bernstein-Yang safegcd
+/// algorithm ("Fast constant-time gcd computation and modular inversion",
+/// 2019). Divsteps are batched 62 at a time into a transition matrix
+/// computed from the low limbs of f and g, and the number of batches only
+/// depends on the bit length of the modulus. Intermediates are fixed width
+/// two's complement limb vectors, kept in (-modulus, modulus) between
+/// batches, and conditional negations and reductions use masks.
+/// a must be less than twice the modulus.
+/// Returns None if a has no inverse.
+pub fn invert_safegcd(a: &IntegerAU, modulus: &IntegerAU) -> Option<IntegerAU> {
+    assert!(modulus.bit(0), "safegcd requires an odd modulus");
+    debug_assert!(a < &(modulus + modulus), "a must be less than 2 * modulus");
+    // one limb of headroom for the 2^62 scaled matrix products and one for
+    // the sign
+    let width = modulus.limbs.len() + 2;
+    let m = ct::pad(modulus.limbs.clone(), width);
+    // a in [p, 2p) is brought into [0, p) with a masked subtraction
+    let a = ct::pad(a.limbs.clone(), width);
+    let (diff, borrow) = ct::sub_borrow(&a, &m);
+    let mut f = m.clone();
+    let mut g = ct::select(ct::mask(borrow ^ 1), &diff, &a);
+    let mut d = vec![0u64; width];
+    let mut e = ct::pad(vec![1], width);
+    let mut delta = 1i64;
+
+    let m_inv = inv_mod_2_64(modulus.limbs[0]);
+    let batch_mask = (1u64 << SAFEGCD_BATCH) - 1;
+    for _ in 0..safegcd_batches(modulus.bit_len()) {
+        let (next_delta, [u, v, q, r]) = divsteps(delta, f[0], g[0]);
+        delta = next_delta;
+
+        let f_next = signed_sar(&signed_lincomb(&f, u, &g, v), SAFEGCD_BATCH);
+        g = signed_sar(&signed_lincomb(&f, q, &g, r), SAFEGCD_BATCH);
+        f = f_next;
+
+        // add a multiple of the modulus so the low bits vanish before
+        // dividing by 2^62, this keeps d and e congruent mod the modulus.
+        // With |u| + |v| <= 2^62 the results are in (-p, 2p).
+        let mut d_next = signed_lincomb(&d, u, &e, v);
+        let mut e_next = signed_lincomb(&d, q, &e, r);
+        for t in [&mut d_next, &mut e_next] {
+            let k = t[0].wrapping_neg().wrapping_mul(m_inv) & batch_mask;
+            let correction = signed_mul(&m, k as i64);
+            *t = signed_sar(&wrapping_add(t, &correction), SAFEGCD_BATCH);
+            *t = signed_csub(t, &m);
+        }
+        d = d_next;
+        e = e_next;
+    }
+
+    // f is now +-gcd(a, modulus) and d * a = f mod modulus
+    let f_sign = ct::mask(f[width - 1] >> 63);
+    let f = cneg(&f, f_sign);
+    if f[0] != 1 || f[1..].iter().any(|&limb| limb != 0) {
+        return None;
+    }
+    let d = cneg(&d, f_sign);
+    Some(signed_reduce(&d, &m))
+}
+
+// Number of 62 divstep batches needed for a modulus of the given bit length,
+// from theorem 11.2 of the safegcd paper
+fn safegcd_batches(bits: usize) -> usize {
+    let divsteps = if bits < 46 {
+        (49 * bits + 80) / 17
+    } else {
+        (49 * bits + 57) / 17
+    };
+    divsteps.div_ceil(SAFEGCD_BATCH as usize)
+}
+
+// Runs 62 divsteps on the low bits of f and g without branching on them
+// Returns the new delta and the matrix [u, v, q, r] such that
+// f' * 2^62 = u * f + v * g and g' * 2^62 = q * f + r * g
+fn divsteps(mut delta: i64, f0: u64, g0: u64) -> (i64, [i64; 4]) {
+    let (mut f, mut g) = (f0, g0);
+    let (mut u, mut v, mut q, mut r) = (1i64, 0i64, 0i64, 1i64);
+    for _ in 0..SAFEGCD_BATCH {
+        // f is always odd, swap and negate when delta > 0 and g is odd
+        let odd = ct::mask(g) as i64;
+        let swap = (delta.wrapping_neg() >> 63) & odd;
+        let t = (f ^ g) & swap as u64;
+        f ^= t;
+        g ^= t;
+        g = (g ^ swap as u64).wrapping_sub(swap as u64);
+        let t = (u ^ q) & swap;
+        u ^= t;
+        q ^= t;
+        q = (q ^ swap) - swap;
+        let t = (v ^ r) & swap;
+        v ^= t;
+        r ^= t;
+        r = (r ^ swap) - swap;
+        delta = (delta ^ swap) - swap;
+
+        // g is still odd if it was before, make it even then halve it
+        g = g.wrapping_add(f & odd as u64);
+        q += u & odd;
+        r += v & odd;
+        g >>= 1;
+        u <<= 1;
+        v <<= 1;
+        delta += 1;
+    }
+    (delta, [u, v, q, r])
+}
+
+/// Inverts `a` modulo an odd modulus of at most 256 bits using the
+/// optimized binary GCD from Pornin ("Optimized Binary GCD for Modular
+/// Inversion", 2020). Each outer iteration runs 31 binary GCD steps on 64 bit
+/// approximations of a and b made of their low 31 bits and their top 33
+/// bits, then applies the accumulated update factors to the full values.
+/// A fixed number of outer iterations is performed for a given modulus size.
+/// Values are held in 5 limbs, 4 for the magnitude and one for the sign of
+/// the intermediate linear combinations.
+/// Returns None if a has no inverse.
+pub fn invert_bingcd(a: &IntegerAU, modulus: &IntegerAU) -> Option<IntegerAU> {
+    assert!(modulus.bit(0), "bingcd requires an odd modulus");
+    assert!(
+        modulus.bit_len() <= BINGCD_MAX_BITS,
+        "bingcd supports moduli up to {BINGCD_MAX_BITS} bits"
+    );
+    const WIDTH: usize = BINGCD_MAX_BITS / 64 + 1;
+    let m = ct::pad(modulus.limbs.clone(), WIDTH);
+    let mut a = ct::pad(a.modulo(modulus).unwrap().limbs, WIDTH);
+    let mut b = m.clone();
+    let mut u = ct::pad(vec![1], WIDTH);
+    let mut v = vec![0u64; WIDTH];
+
+    let m_inv = inv_mod_2_64(m[0]);
+    let low_mask = (1u64 << BINGCD_BATCH) - 1;
+    // 2 * len(m) - 1 steps always bring a to zero
+    let outer = (2 * modulus.bit_len() - 1).div_ceil(BINGCD_BATCH as usize);
+    for _ in 0..outer {
+        // approximations keep the low 31 bits and the top 33 bits of the
+        // longer value, they are exact once both values fit in 64 bits
+        let n = bit_len(&a).max(bit_len(&b)).max(64);
+        let a_approx = (a[0] & low_mask) | (ct::shr(&a, n - 33, 1)[0] << BINGCD_BATCH);
+        let b_approx = (b[0] & low_mask) | (ct::shr(&b, n - 33, 1)[0] << BINGCD_BATCH);
+        let [mut f0, mut g0, mut f1, mut g1] = bingcd_steps(a_approx, b_approx);
+
+        let a_next = signed_sar(&signed_lincomb(&a, f0, &b, g0), BINGCD_BATCH);
+        let b_next = signed_sar(&signed_lincomb(&a, f1, &b, g1), BINGCD_BATCH);
+        // the approximations may pick the wrong branch, which can only turn
+        // a value negative, fix it and the factors that produced it
+        let a_sign = ct::mask(a_next[WIDTH - 1] >> 63);
+        let b_sign = ct::mask(b_next[WIDTH - 1] >> 63);
+        a = cneg(&a_next, a_sign);
+        b = cneg(&b_next, b_sign);
+        f0 = (f0 ^ a_sign as i64) - a_sign as i64;
+        g0 = (g0 ^ a_sign as i64) - a_sign as i64;
+        f1 = (f1 ^ b_sign as i64) - b_sign as i64;
+        g1 = (g1 ^ b_sign as i64) - b_sign as i64;
+
+        let u_next = bingcd_update(&u, f0, &v, g0, &m, m_inv);
+        v = bingcd_update(&u, f1, &v, g1, &m, m_inv);
+        u = u_next;
+    }
+
+    // a is zero and b is gcd(a, m) = a * v mod m
+    if b[0] != 1 || b[1..].iter().any(|&limb| limb != 0) {
+        return None;
+    }
+    Some(IntegerAU { limbs: v }.trim())
+}
+
+// Runs the inner binary GCD steps on the approximations of a and b
+// Returns the update factors [f0, g0, f1, g1] such that
+// a' * 2^31 = f0 * a + g0 * b and b' * 2^31 = f1 * a + g1 * b
+fn bingcd_steps(mut a: u64, mut b: u64) -> [i64; 4] {
+    let (mut f0, mut g0, mut f1, mut g1) = (1i64, 0i64, 0i64, 1i64);
+    for _ in 0..BINGCD_BATCH {
+        // if a is odd and smaller than b swap them, then subtract b from a
+        let odd = ct::mask(a);
+        let swap = ct::mask((a < b) as u64) & odd;
+        let t = (a ^ b) & swap;
+        a ^= t;
+        b ^= t;
+        let t = (f0 ^ f1) & swap as i64;
+        f0 ^= t;
+        f1 ^= t;
+        let t = (g0 ^ g1) & swap as i64;
+        g0 ^= t;
+        g1 ^= t;
+
+        a = a.wrapping_sub(b & odd);
+        f0 -= f1 & odd as i64;
+        g0 -= g1 & odd as i64;
+        a >>= 1;
+        f1 <<= 1;
+        g1 <<= 1;
+    }
+    [f0, g0, f1, g1]
+}
+
+// Computes (f * u + g * v) / 2^31 mod m for u, v in [0, m)
+fn bingcd_update(u: &[u64], f: i64, v: &[u64], g: i64, m: &[u64], m_inv: u64) -> Vec<u64> {
+    let low_mask = (1u64 << BINGCD_BATCH) - 1;
+    let t = signed_lincomb(u, f, v, g);
+    // make the low bits vanish so the division is exact, t is then in
+    // (-m, 2m)
+    let k = t[0].wrapping_neg().wrapping_mul(m_inv) & low_mask;
+    let t = signed_sar(&wrapping_add(&t, &signed_mul(m, k as i64)), BINGCD_BATCH);
+    let t = wrapping_add(
+        &t,
+        &ct::and(m, &vec![ct::mask(t[t.len() - 1] >> 63); m.len()]),
+    );
+    let (diff, borrow) = ct::sub_borrow(&t, m);
+    ct::select(ct::mask(borrow ^ 1), &diff, &t)
+}
+
+// Bit length of a non-negative two's complement value
+fn bit_len(a: &[u64]) -> usize {
+    IntegerAU { limbs: a.to_vec() }.trim().bit_len()
+}
+
+// Inverse of an odd limb modulo 2^64 by Newton iteration
+fn inv_mod_2_64(x: u64) -> u64 {
+    // correct to 3 bits, each step doubles the number of correct bits
+    let mut inv = x;
+    for _ in 0..5 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(x.wrapping_mul(inv)));
+    }
+    inv
+}
+
+// Helpers on fixed width two's complement limb vectors. All of them wrap
+// modulo 2^(64 * width), callers size the width so nothing overflows.
+
+// Negates `a` if the mask is set
+fn cneg(a: &[u64], mask: u64) -> Vec<u64> {
+    let mut carry = mask & 1;
+    a.iter()
+        .map(|&x| {
+            let (sum, overflow) = (x ^ mask).overflowing_add(carry);
+            carry = overflow as u64;
+            sum
+        })
+        .collect()
+}
+
+fn wrapping_add(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut sum = ct::add(a, b);
+    sum.truncate(a.len());
+    sum
+}
+
+// Multiplies by a signed 64 bit value
+fn signed_mul(a: &[u64], m: i64) -> Vec<u64> {
+    let sign = m >> 63;
+    let magnitude = ((m ^ sign) - sign) as u64;
+    let a_sign = ct::mask(a[a.len() - 1] >> 63);
+    let a_abs = cneg(a, a_sign);
+    let mut product = ct::mul(&a_abs, &[magnitude]);
+    product.truncate(a.len());
+    cneg(&product, a_sign ^ sign as u64)
+}
+
+// u * a + v * b
+fn signed_lincomb(a: &[u64], u: i64, b: &[u64], v: i64) -> Vec<u64> {
+    wrapping_add(&signed_mul(a, u), &signed_mul(b, v))
+}
+
+// Arithmetic right shift by less than 64 bits
+fn signed_sar(a: &[u64], shift: u32) -> Vec<u64> {
+    let sign = ct::mask(a[a.len() - 1] >> 63);
+    (0..a.len())
+        .map(|i| {
+            let hi = a.get(i + 1).copied().unwrap_or(sign);
+            (a[i] >> shift) | (hi << (64 - shift))
+        })
+        .collect()
+}
+
+// Maps a signed value in (-m, 2m) into (-m, m), subtracting m unless the
+// difference is negative
+fn signed_csub(a: &[u64], m: &[u64]) -> Vec<u64> {
+    let (diff, _) = ct::sub_borrow(a, m);
+    let negative = ct::mask(diff[diff.len() - 1] >> 63);
+    ct::select(negative, a, &diff)
+}
+
+// Maps a signed value in (-m, m) into [0, m), adding m if it is negative
+fn signed_reduce(a: &[u64], m: &[u64]) -> IntegerAU {
+    let sign = ct::mask(a[a.len() - 1] >> 63);
+    IntegerAU {
+        limbs: wrapping_add(a, &ct::and(m, &vec![sign; m.len()])),
+    }
+    .trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::barrett::Barrett;
+    use crate::montgomery::Montgomery;
+    use crate::seed::test_rng;
+    use crate::PRIMES;
+    use rand::Rng;
+    use std::str::FromStr;
+
+    fn check_batch_invert<R: Reducer>(reducer: &R, p: &IntegerAU) {
+        let mut rng = test_rng();
+        let p_big = p.to_biguint();
+        for len in [0, 1, 2, 17] {
+            let mut values = (0..len)
+                .map(|_| IntegerAU::random_below_with(p, &mut rng))
+                .collect::<Vec<_>>();
+            // zeros at the ends and in the middle
+            if len > 1 {
+                values[0] = IntegerAU::from(0);
+                values[len / 2] = IntegerAU::from(0);
+            }
+            let mut batch = values
+                .iter()
+                .map(|v| reducer.to_internal(v))
+                .collect::<Vec<_>>();
+            reducer.batch_invert(&mut batch);
+
+            for (v, inv) in values.iter().zip(&batch) {
+                let inv = reducer.to_canonical(inv);
+                match v.to_biguint().modinv(&p_big) {
+                    Some(expected) => assert_eq!(
+                        inv.to_biguint(),
+                        expected,
+                        "Failed batch inversion test: {}^-1 mod {}",
+                        v,
+                        p
+                    ),
+                    None => assert!(inv.is_zero(), "zero must stay zero"),
+                }
+                let single = reducer.invert(&reducer.to_internal(v));
+                assert_eq!(
+                    single.map(|i| reducer.to_canonical(&i)),
+                    v.to_biguint().modinv(&p_big).map(IntegerAU::from_biguint)
+                );
+            }
+        }
+
+        let mut zeros = vec![IntegerAU::from(0); 3];
+        reducer.batch_invert(&mut zeros);
+        assert!(zeros.iter().all(IntegerAU::is_zero));
+    }
+
+    #[test]
+    fn test_safegcd() {
+        let mut rng = test_rng();
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            let p_big = p.to_biguint();
+            let mut values = vec![
+                IntegerAU::from(1),
+                IntegerAU::from(2),
+                &p - &IntegerAU::from(1),
+                &p + &IntegerAU::from(3),
+            ];
+            values.extend((0..100).map(|_| IntegerAU::random_below_with(&p, &mut rng)));
+            for v in values {
+                let expected = v.to_biguint().modinv(&p_big);
+                assert_eq!(
+                    invert_safegcd(&v, &p).map(|i| i.to_biguint()),
+                    expected,
+                    "Failed safegcd test: {}^-1 mod {}",
+                    v,
+                    p
+                );
+            }
+            assert_eq!(invert_safegcd(&IntegerAU::from(0), &p), None);
+            assert_eq!(invert_safegcd(&p, &p), None);
+        }
+    }
+
+    #[test]
+    fn test_safegcd_composite() {
+        let mut rng = test_rng();
+        for _ in 0..200 {
+            // random odd moduli of 1 to 8 limbs
+            let m_limbs = rng.gen_range(1..=8);
+            let mut m = IntegerAU {
+                limbs: (0..m_limbs).map(|_| rng.gen::<u64>()).collect(),
+            }
+            .trim();
+            m.limbs[0] |= 1;
+            if m == IntegerAU::from(1) {
+                continue;
+            }
+            let v = IntegerAU::random_below_with(&m, &mut rng);
+            assert_eq!(
+                invert_safegcd(&v, &m).map(|i| i.to_biguint()),
+                v.to_biguint().modinv(&m.to_biguint()),
+                "Failed safegcd test: {}^-1 mod {}",
+                v,
+                m
+            );
+        }
+        // shares a factor of 3 with the modulus
+        assert_eq!(
+            invert_safegcd(&IntegerAU::from(6), &IntegerAU::from(15)),
+            None
+        );
+        assert_eq!(
+            invert_safegcd(&IntegerAU::from(7), &IntegerAU::from(15)),
+            Some(IntegerAU::from(13))
+        );
+    }
+
+    #[test]
+    fn test_bingcd() {
+        let mut rng = test_rng();
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            let p_big = p.to_biguint();
+            let mut values = vec![
+                IntegerAU::from(1),
+                IntegerAU::from(2),
+                &p - &IntegerAU::from(1),
+                &p + &IntegerAU::from(3),
+            ];
+            values.extend((0..1000).map(|_| IntegerAU::random_below_with(&p, &mut rng)));
+            for v in values {
+                assert_eq!(
+                    invert_bingcd(&v, &p).map(|i| i.to_biguint()),
+                    v.to_biguint().modinv(&p_big),
+                    "Failed bingcd test: {}^-1 mod {}",
+                    v,
+                    p
+                );
+            }
+            assert_eq!(invert_bingcd(&IntegerAU::from(0), &p), None);
+        }
+    }
+
+    #[test]
+    fn test_bingcd_composite() {
+        let mut rng = test_rng();
+        for _ in 0..1000 {
+            // random odd moduli of 1 to 4 limbs
+            let m_limbs = rng.gen_range(1..=4);
+            let mut m = IntegerAU {
+                limbs: (0..m_limbs).map(|_| rng.gen::<u64>()).collect(),
+            }
+            .trim();
+            m.limbs[0] |= 1;
+            if m == IntegerAU::from(1) {
+                continue;
+            }
+            let v = IntegerAU::random_below_with(&m, &mut rng);
+            assert_eq!(
+                invert_bingcd(&v, &m).map(|i| i.to_biguint()),
+                v.to_biguint().modinv(&m.to_biguint()),
+                "Failed bingcd test: {}^-1 mod {}",
+                v,
+                m
+            );
+        }
+        assert_eq!(
+            invert_bingcd(&IntegerAU::from(6), &IntegerAU::from(15)),
+            None
+        );
+        assert_eq!(
+            invert_bingcd(&IntegerAU::from(7), &IntegerAU::from(15)),
+            Some(IntegerAU::from(13))
+        );
+    }
+
+    #[test]
+    fn test_safegcd_montgomery_form() {
+        let mut rng = test_rng();
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            let montgomery = Montgomery::new(&p);
+            let v = montgomery.to_internal(&IntegerAU::random_below_with(&p, &mut rng));
+            assert_eq!(montgomery.invert_safegcd(&v), montgomery.invert(&v));
+        }
+    }
+
+    #[test]
+    fn test_batch_invert_barrett() {
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            check_batch_invert(&Barrett::new(p.clone()), &p);
+        }
+    }
+
+    #[test]
+    fn test_batch_invert_montgomery() {
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            check_batch_invert(&Montgomery::new(&p), &p);
+        }
+    }
+}
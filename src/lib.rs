@@ -0,0 +1,62 @@
+// Implements a binary operator for owned and mixed operands of `$t` by
+// delegating to the implementation on two references. Defined before the
+// modules so all of them can use it.
+macro_rules! forward_owned_binop {
+    ($t:ty; $($imp:ident, $method:ident;)*) => {
+        $(
+            impl $imp for $t {
+                type Output = $t;
+
+                fn $method(self, other: $t) -> $t {
+                    (&self).$method(&other)
+                }
+            }
+
+            impl $imp<$t> for &$t {
+                type Output = $t;
+
+                fn $method(self, other: $t) -> $t {
+                    self.$method(&other)
+                }
+            }
+
+            impl<'a> $imp<&'a $t> for $t {
+                type Output = $t;
+
+                fn $method(self, other: &'a $t) -> $t {
+                    (&self).$method(other)
+                }
+            }
+        )*
+    };
+}
+
+pub mod barrett;
+pub mod compare;
+pub mod ct;
+pub mod dudect;
+pub mod integer_as;
+pub mod integer_au;
+pub mod inversion;
+pub mod moduli;
+pub mod montgomery;
+pub mod naive;
+pub mod op_count;
+pub mod pow;
+pub mod prime;
+pub mod reducer;
+pub mod report;
+pub mod seed;
+pub mod sqrt;
+pub mod stats;
+pub mod workload;
+
+use integer_au::IntegerAU;
+
+pub static PRIMES: [&str; 5] = [
+    "2013265921",
+    "18446744069414584321",
+    "170141183460469231731687303715884105727",
+    "340282366920938463463374607431768211507",
+    "57896044618658097711785492504343953926634992332820282019728792003956564819949",
+];
@@ -1,43 +1,65 @@
+use std::fmt::Display;
 use std::str::FromStr;
-use std::time::Instant;
 
+use clap::Parser;
 use num_bigint::BigUint;
+use rand::rngs::StdRng;
 
-mod barrett;
-mod integer_au;
-mod montgomery;
+use moduli_comparison::barrett::Barrett;
+use moduli_comparison::integer_au::IntegerAU;
+use moduli_comparison::montgomery::Montgomery;
+use moduli_comparison::pow;
+use moduli_comparison::reducer::Reducer;
+use moduli_comparison::reducer::Scratch;
+use moduli_comparison::seed;
+use moduli_comparison::sqrt;
+use moduli_comparison::PRIMES;
 
-use barrett::Barrett;
-use integer_au::IntegerAU;
-use montgomery::Montgomery;
+mod cli;
 
-static PRIMES: [&str; 5] = [
-    "2013265921",
-    "18446744069414584321",
-    "170141183460469231731687303715884105727",
-    "340282366920938463463374607431768211507",
-    "57896044618658097711785492504343953926634992332820282019728792003956564819949",
-];
+// Exponent sizes used by the exponentiation benchmarks
+const EXPONENT_BITS: [usize; 4] = [64, 256, 1024, 4096];
+// Window size used by the windowed exponentiation benchmarks
+const POW_WINDOW: usize = 4;
+// Number of values inverted at once by the batch inversion benchmarks
+const BATCH_SIZES: [usize; 5] = [1, 16, 256, 4096, 65536];
 
 fn main() -> anyhow::Result<()> {
-    // Run registered benchmarks.
-    divan::main();
+    // `cargo bench` passes --bench, run the registered divan benchmarks
+    if std::env::args().any(|arg| arg == "--bench") {
+        eprintln!(
+            "Benchmark inputs seeded with {}={}",
+            seed::SEED_ENV,
+            seed::global_seed()
+        );
+        divan::main();
+        return Ok(());
+    }
+    cli::run(&cli::Args::parse())
+}
 
-    let primes = PRIMES
-        .iter()
-        .map(|p| IntegerAU::from_biguint(BigUint::from_str(p).unwrap()))
-        .collect::<Vec<_>>();
-    benchmark_muls(&primes)?;
-    benchmark_muls_sum(&primes)?;
-    Ok(())
+// RNG for the inputs of one benchmark, seeded from the global seed, the
+// benchmark name and its arguments so the inputs don't depend on which
+// benchmarks run before it
+fn bench_rng(name: &str, args: impl Display) -> StdRng {
+    seed::rng(seed::derive(
+        seed::global_seed(),
+        &format!("{name}({args})"),
+    ))
+}
+
+// Uniform value below upper
+fn random_below(upper: &IntegerAU, rng: &mut StdRng) -> IntegerAU {
+    IntegerAU::random_below_with(upper, rng)
 }
 
 #[divan::bench(args = PRIMES)]
 fn bench_barrett(bencher: divan::Bencher, prime_str: &str) {
-    let p = IntegerAU::from_biguint(BigUint::from_str(prime_str).unwrap());
+    let mut rng = bench_rng("bench_barrett", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
     let barrett_reducer = Barrett::new(p.clone());
-    let x = &IntegerAU::random_below(&p);
-    let y = &IntegerAU::random_below(&p);
+    let x = &random_below(&p, &mut rng);
+    let y = &random_below(&p, &mut rng);
     bencher.bench_local(move || {
         let _reduced = barrett_reducer.reduce(&(x * y));
     });
@@ -45,18 +67,25 @@ fn bench_barrett(bencher: divan::Bencher, prime_str: &str) {
 
 #[divan::bench(args = PRIMES)]
 fn bench_biguint(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_biguint", prime_str);
     let p = BigUint::from_str(prime_str).unwrap();
     let p_int = IntegerAU::from_biguint(p.clone());
-    let x = &IntegerAU::random_below(&p_int).to_biguint();
-    let y = &IntegerAU::random_below(&p_int).to_biguint();
+    let x = &random_below(&p_int, &mut rng).to_biguint();
+    let y = &random_below(&p_int, &mut rng).to_biguint();
     bencher.bench_local(move || {
         let _reduce = x * y % &p;
     });
 }
 
-#[divan::bench(args = PRIMES)]
-fn bench_barrett_poseidon_approx(bencher: divan::Bencher, prime_str: &str) {
-    let p = IntegerAU::from_biguint(BigUint::from_str(prime_str).unwrap());
+// Poseidon approximation bodies, `square` is the unreduced squaring so
+// squaring through the product can be compared with `IntegerAU::square`
+fn barrett_poseidon_approx(
+    bencher: divan::Bencher,
+    prime_str: &str,
+    mut rng: StdRng,
+    square: fn(&IntegerAU) -> IntegerAU,
+) {
+    let p = IntegerAU::from_str(prime_str).unwrap();
     let barrett_reducer = Barrett::new(p.clone());
     let num_rounds = 70;
 
@@ -66,56 +95,48 @@ fn bench_barrett_poseidon_approx(bencher: divan::Bencher, prime_str: &str) {
 
     // we'll sample some pretend round constants
     let round_constants = (0..(num_rounds * state.len()))
-        .map(|_| IntegerAU::random_below(&p))
+        .map(|_| random_below(&p, &mut rng))
         .collect::<Vec<_>>();
     // let m_box = (0..state.len())
-    //     .map(|_| IntegerAU::random_below(&p))
+    //     .map(|_| random_below(&p))
     //     .collect::<Vec<_>>();
     bencher.bench_local(move || {
         let pow5 = |x: &IntegerAU| {
-            let x2 = barrett_reducer.reduce(&(x * x));
-            let x4 = barrett_reducer.reduce(&(&x2 * &x2));
+            let x2 = barrett_reducer.reduce(&square(x));
+            let x4 = barrett_reducer.reduce(&square(&x2));
             barrett_reducer.reduce(&(&x4 * x))
         };
-        let simple_reduce = |x: &IntegerAU| {
-            if x >= &p {
-                x - &p
-            } else {
-                x.clone()
-            }
-        };
         for i in 0..num_rounds {
             // add the round constants
-            state[0] += &round_constants[i * state_len + 0];
-            // state[0] = simple_reduce(&state[0]);
+            state[0] += &round_constants[i * state_len];
             state[1] += &round_constants[i * state_len + 1];
-            // state[1] = simple_reduce(&state[1]);
             state[2] += &round_constants[i * state_len + 2];
-            // state[2] = simple_reduce(&state[2]);
             // pow5, pretend every round is a full round
             state[0] = pow5(&state[0]);
             state[1] = pow5(&state[1]);
             state[2] = pow5(&state[2]);
             //
-            state[0] = barrett_reducer.reduce(&(&state[0] * &state[0]));
-            state[0] = barrett_reducer.reduce(&(&state[0] * &state[0]));
-            state[0] = barrett_reducer.reduce(&(&state[0] * &state[0]));
-            state[1] = barrett_reducer.reduce(&(&state[1] * &state[1]));
-            state[1] = barrett_reducer.reduce(&(&state[1] * &state[1]));
-            state[1] = barrett_reducer.reduce(&(&state[1] * &state[1]));
-            state[2] = barrett_reducer.reduce(&(&state[2] * &state[2]));
-            state[2] = barrett_reducer.reduce(&(&state[2] * &state[2]));
-            state[2] = barrett_reducer.reduce(&(&state[2] * &state[2]));
+            state[0] = barrett_reducer.reduce(&square(&state[0]));
+            state[0] = barrett_reducer.reduce(&square(&state[0]));
+            state[0] = barrett_reducer.reduce(&square(&state[0]));
+            state[1] = barrett_reducer.reduce(&square(&state[1]));
+            state[1] = barrett_reducer.reduce(&square(&state[1]));
+            state[1] = barrett_reducer.reduce(&square(&state[1]));
+            state[2] = barrett_reducer.reduce(&square(&state[2]));
+            state[2] = barrett_reducer.reduce(&square(&state[2]));
+            state[2] = barrett_reducer.reduce(&square(&state[2]));
         }
     });
 }
 
-#[divan::bench(args = PRIMES)]
-fn bench_montgomery_poseidon_approx(bencher: divan::Bencher, prime_str: &str) {
-    let p = IntegerAU::from_biguint(BigUint::from_str(prime_str).unwrap());
+fn montgomery_poseidon_approx(
+    bencher: divan::Bencher,
+    prime_str: &str,
+    mut rng: StdRng,
+    square: fn(&IntegerAU) -> IntegerAU,
+) {
+    let p = IntegerAU::from_str(prime_str).unwrap();
     let montgomery = Montgomery::new(&p);
-    let x = &montgomery.to_mont(&IntegerAU::random_below(&p));
-    let y = &montgomery.to_mont(&IntegerAU::random_below(&p));
     let num_rounds = 70;
 
     let state_len = 3;
@@ -124,15 +145,15 @@ fn bench_montgomery_poseidon_approx(bencher: divan::Bencher, prime_str: &str) {
 
     // we'll sample some pretend round constants
     let round_constants = (0..(num_rounds * state.len()))
-        .map(|_| montgomery.to_mont(&IntegerAU::random_below(&p)))
+        .map(|_| montgomery.to_mont(&random_below(&p, &mut rng)))
         .collect::<Vec<_>>();
     // let m_box = (0..state.len())
-    //     .map(|_| IntegerAU::random_below(&p))
+    //     .map(|_| random_below(&p))
     //     .collect::<Vec<_>>();
     bencher.bench_local(move || {
         let pow5 = |x: &IntegerAU| {
-            let x2 = montgomery.redc(&(x * x));
-            let x4 = montgomery.redc(&(&x2 * &x2));
+            let x2 = montgomery.redc(&square(x));
+            let x4 = montgomery.redc(&square(&x2));
             montgomery.redc(&(&x4 * x))
         };
         let simple_reduce = |x: &IntegerAU| {
@@ -144,7 +165,7 @@ fn bench_montgomery_poseidon_approx(bencher: divan::Bencher, prime_str: &str) {
         };
         for i in 0..num_rounds {
             // add the round constants
-            state[0] += &round_constants[i * state_len + 0];
+            state[0] += &round_constants[i * state_len];
             state[0] = simple_reduce(&state[0]);
             state[1] += &round_constants[i * state_len + 1];
             state[1] = simple_reduce(&state[1]);
@@ -154,221 +175,440 @@ fn bench_montgomery_poseidon_approx(bencher: divan::Bencher, prime_str: &str) {
             state[0] = pow5(&state[0]);
             state[1] = pow5(&state[1]);
             state[2] = pow5(&state[2]);
-            state[0] = montgomery.redc(&(&state[0] * &state[0]));
-            state[0] = montgomery.redc(&(&state[0] * &state[0]));
-            state[0] = montgomery.redc(&(&state[0] * &state[0]));
-            state[1] = montgomery.redc(&(&state[1] * &state[1]));
-            state[1] = montgomery.redc(&(&state[1] * &state[1]));
-            state[1] = montgomery.redc(&(&state[1] * &state[1]));
-            state[2] = montgomery.redc(&(&state[2] * &state[2]));
-            state[2] = montgomery.redc(&(&state[2] * &state[2]));
-            state[2] = montgomery.redc(&(&state[2] * &state[2]));
+            state[0] = montgomery.redc(&square(&state[0]));
+            state[0] = montgomery.redc(&square(&state[0]));
+            state[0] = montgomery.redc(&square(&state[0]));
+            state[1] = montgomery.redc(&square(&state[1]));
+            state[1] = montgomery.redc(&square(&state[1]));
+            state[1] = montgomery.redc(&square(&state[1]));
+            state[2] = montgomery.redc(&square(&state[2]));
+            state[2] = montgomery.redc(&square(&state[2]));
+            state[2] = montgomery.redc(&square(&state[2]));
         }
         montgomery.from_mont(&state[0]);
     });
 }
 
+#[divan::bench(args = PRIMES)]
+fn bench_barrett_poseidon_approx(bencher: divan::Bencher, prime_str: &str) {
+    let rng = bench_rng("bench_barrett_poseidon_approx", prime_str);
+    barrett_poseidon_approx(bencher, prime_str, rng, |x| x * x);
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_barrett_poseidon_approx_square(bencher: divan::Bencher, prime_str: &str) {
+    let rng = bench_rng("bench_barrett_poseidon_approx_square", prime_str);
+    barrett_poseidon_approx(bencher, prime_str, rng, IntegerAU::square);
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_montgomery_poseidon_approx(bencher: divan::Bencher, prime_str: &str) {
+    let rng = bench_rng("bench_montgomery_poseidon_approx", prime_str);
+    montgomery_poseidon_approx(bencher, prime_str, rng, |x| x * x);
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_montgomery_poseidon_approx_square(bencher: divan::Bencher, prime_str: &str) {
+    let rng = bench_rng("bench_montgomery_poseidon_approx_square", prime_str);
+    montgomery_poseidon_approx(bencher, prime_str, rng, IntegerAU::square);
+}
+
 #[divan::bench(args = PRIMES)]
 fn bench_montgomery(bencher: divan::Bencher, prime_str: &str) {
-    let p = IntegerAU::from_biguint(BigUint::from_str(prime_str).unwrap());
+    let mut rng = bench_rng("bench_montgomery", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
     let montgomery = Montgomery::new(&p);
-    let x = &montgomery.to_mont(&IntegerAU::random_below(&p));
-    let y = &montgomery.to_mont(&IntegerAU::random_below(&p));
+    let x = &montgomery.to_mont(&random_below(&p, &mut rng));
+    let y = &montgomery.to_mont(&random_below(&p, &mut rng));
     bencher.bench_local(move || {
         let _z = montgomery.from_mont(&montgomery.redc(&(x * y)));
     });
 }
 
+#[divan::bench(args = PRIMES)]
+fn bench_barrett_into(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_barrett_into", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let barrett = Barrett::new(p.clone());
+    let x = &random_below(&p, &mut rng);
+    let y = &random_below(&p, &mut rng);
+    let mut out = IntegerAU::from(0);
+    let mut scratch = Scratch::new();
+    bencher.bench_local(move || {
+        barrett.mul_into(x, y, &mut out, &mut scratch);
+    });
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_montgomery_into(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_montgomery_into", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let x = &montgomery.to_mont(&random_below(&p, &mut rng));
+    let y = &montgomery.to_mont(&random_below(&p, &mut rng));
+    let mut out = IntegerAU::from(0);
+    let mut scratch = Scratch::new();
+    bencher.bench_local(move || {
+        montgomery.mul_into(x, y, &mut out, &mut scratch);
+    });
+}
+
 #[divan::bench(args = PRIMES)]
 fn bench_naive(bencher: divan::Bencher, prime_str: &str) {
-    let p = IntegerAU::from_biguint(BigUint::from_str(prime_str).unwrap());
-    let x = &IntegerAU::random_below(&p);
-    let y = &IntegerAU::random_below(&p);
+    let mut rng = bench_rng("bench_naive", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let x = &random_below(&p, &mut rng);
+    let y = &random_below(&p, &mut rng);
     bencher.bench_local(move || {
         let _z = (x * y) % p.clone();
     });
 }
 
-// Benchmark a sequence of multiplications between random values
-// For montgomery we assume the values are already in montgomery form
-// and extract the final value into base field representation
-
-// Barrett generally performs better here
-fn benchmark_muls(primes: &Vec<IntegerAU>) -> anyhow::Result<()> {
-    let iterations = 1000;
-    println!("\nBenchmarking multiplications between random values.");
-    println!("For Montgomery we assume the inputs are already in");
-    println!("Montgomery form. The final result is converted to");
-    println!("field representation.");
-    for p in primes {
-        println!("\n===== modulus {p} ({} bits) =====", p.bit_len());
-        let barrett_reducer = Barrett::new(p.clone());
-        // sample the integers before we starting timing
-        // rejection sampling smh
-        let values = (0..iterations)
-            .into_iter()
-            .map(|_| {
-                let x = IntegerAU::random_below(&p);
-                let y = IntegerAU::random_below(&p);
-                (x, y)
-            })
-            .collect::<Vec<_>>();
-        let mut expected = Vec::with_capacity(iterations);
-        let start = Instant::now();
-        for (x, y) in &values {
-            expected.push((x * y) % p.clone());
-        }
-        println!(
-            "Naïve time for {iterations} multiplications: {:?}",
-            start.elapsed()
-        );
+#[divan::bench(args = PRIMES)]
+fn bench_mul_self(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_mul_self", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let x = &random_below(&p, &mut rng);
+    bencher.bench_local(move || x * x);
+}
 
-        let mut barrett_result = Vec::with_capacity(iterations);
-        let start = Instant::now();
-        for (x, y) in &values {
-            // the value before reduction
-            let z = x * y;
-            let reduced = barrett_reducer.reduce(&z);
-            barrett_result.push(reduced);
-        }
-        println!(
-            "Barrett time for {iterations} multiplications: {:?}",
-            start.elapsed()
-        );
+#[divan::bench(args = PRIMES)]
+fn bench_square(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_square", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let x = &random_below(&p, &mut rng);
+    bencher.bench_local(move || x.square());
+}
 
-        let mut mont_result = Vec::with_capacity(iterations);
-        let montgomery = Montgomery::new(&p);
-        let mont_vals = values
-            .iter()
-            .map(|(x, y)| (montgomery.to_mont(x), montgomery.to_mont(y)))
-            .collect::<Vec<_>>();
-        let start = Instant::now();
-        for (x, y) in &mont_vals {
-            // the value before reduction
-            let z = montgomery.from_mont(&montgomery.redc(&(x * y)));
-            mont_result.push(z);
-        }
-        println!(
-            "Montgomery time for {iterations} multiplications: {:?}",
-            start.elapsed()
-        );
-        for i in 0..iterations {
-            assert_eq!(
-                expected[i], barrett_result[i],
-                "barrett reduction mismatches naive reduction"
-            );
-            assert_eq!(
-                expected[i], mont_result[i],
-                "montgomery reduction mismatches naive reduction"
-            );
-        }
-    }
-    Ok(())
-}
-
-fn benchmark_muls_sum(primes: &Vec<IntegerAU>) -> anyhow::Result<()> {
-    let iterations = 10000;
-    println!("\nBenchmarking multiplications and then summation");
-    println!("between random values.");
-    println!("For Montgomery we assume the inputs are already in");
-    println!("Montgomery form. The final result is converted to");
-    println!("field representation.");
-    for p in primes {
-        println!("\n===== modulus {p} ({} bits) =====", p.bit_len());
-        // sample the integers before we starting timing
-        // rejection sampling smh
-        let values = (0..iterations)
-            .into_iter()
-            .map(|_| {
-                let x = IntegerAU::random_below(&p);
-                let y = IntegerAU::random_below(&p);
-                (x, y)
-            })
-            .collect::<Vec<_>>();
-        let mut expected = Vec::with_capacity(iterations);
-        let start = Instant::now();
-        for (x, y) in &values {
-            // the value before reduction
-            let z = (x * y) % p.clone();
-            expected.push(z);
-        }
-        let expected_out = expected
-            .iter()
-            .fold(IntegerAU::from(0), |acc, x| (&acc + x) % p.clone());
-        println!(
-            "Naïve time for {iterations} multiplications and summation: {:?}",
-            start.elapsed()
-        );
-        let mut barrett_result = Vec::with_capacity(iterations);
-        let barrett_reducer = Barrett::new(p.clone());
-        let start = Instant::now();
-        for (x, y) in &values {
-            // the value before reduction
-            let z = x * y;
-            let reduced = barrett_reducer.reduce(&z);
-            barrett_result.push(reduced);
-        }
-        let barrett_out = barrett_result.iter().fold(IntegerAU::from(0), |acc, x| {
-            barrett_reducer.reduce(&(&acc + x))
-        });
-        println!(
-            "Barrett time for {iterations} multiplications and summation: {:?}",
-            start.elapsed()
-        );
-        let mut mont_result = Vec::with_capacity(iterations);
-        let montgomery = Montgomery::new(&p);
-        let mont_vals = values
-            .iter()
-            .map(|(x, y)| (montgomery.to_mont(x), montgomery.to_mont(y)))
-            .collect::<Vec<_>>();
-        let start = Instant::now();
-        for (x, y) in &mont_vals {
-            // the value before reduction
-            let z = montgomery.redc(&(x * y));
-            mont_result.push(z);
-        }
-        let mont_out = mont_result.iter().fold(IntegerAU::from(0), |acc, x| {
-            montgomery.reduce_naive(&(&acc + x))
-        });
-        let mont_out = montgomery.from_mont(&mont_out);
-        println!(
-            "Montgomery time for {iterations} multiplications and summation: {:?}",
-            start.elapsed()
-        );
-        let biguint_values = values
+// x^5 as the Poseidon s-box computes it, squarings through `mul` or `square`
+fn pow5_mul<R: Reducer>(reducer: &R, x: &IntegerAU) -> IntegerAU {
+    let x2 = reducer.mul(x, x);
+    let x4 = reducer.mul(&x2, &x2);
+    reducer.mul(&x4, x)
+}
+
+fn pow5_square<R: Reducer>(reducer: &R, x: &IntegerAU) -> IntegerAU {
+    let x4 = reducer.square(&reducer.square(x));
+    reducer.mul(&x4, x)
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_barrett_pow5_mul(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_barrett_pow5_mul", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let barrett_reducer = Barrett::new(p.clone());
+    let x = random_below(&p, &mut rng);
+    bencher.bench_local(move || pow5_mul(&barrett_reducer, &x));
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_barrett_pow5_square(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_barrett_pow5_square", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let barrett_reducer = Barrett::new(p.clone());
+    let x = random_below(&p, &mut rng);
+    bencher.bench_local(move || pow5_square(&barrett_reducer, &x));
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_montgomery_pow5_mul(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_montgomery_pow5_mul", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let x = montgomery.to_mont(&random_below(&p, &mut rng));
+    bencher.bench_local(move || pow5_mul(&montgomery, &x));
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_montgomery_pow5_square(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_montgomery_pow5_square", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let x = montgomery.to_mont(&random_below(&p, &mut rng));
+    bencher.bench_local(move || pow5_square(&montgomery, &x));
+}
+
+// A random square in the reducer's representation
+fn square_input<R: Reducer>(reducer: &R, rng: &mut StdRng) -> IntegerAU {
+    reducer.square(&reducer.to_internal(&random_below(reducer.modulus(), rng)))
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_barrett_sqrt(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_barrett_sqrt", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let barrett_reducer = Barrett::new(p);
+    let a = square_input(&barrett_reducer, &mut rng);
+    bencher.bench_local(move || barrett_reducer.sqrt(&a));
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_barrett_legendre(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_barrett_legendre", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let barrett_reducer = Barrett::new(p);
+    let a = square_input(&barrett_reducer, &mut rng);
+    bencher.bench_local(move || barrett_reducer.legendre(&a));
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_montgomery_sqrt(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_montgomery_sqrt", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let a = square_input(&montgomery, &mut rng);
+    bencher.bench_local(move || montgomery.sqrt(&a));
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_montgomery_sqrt_tonelli_shanks(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_montgomery_sqrt_tonelli_shanks", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let a = square_input(&montgomery, &mut rng);
+    bencher.bench_local(move || sqrt::sqrt_tonelli_shanks(&montgomery, &a));
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_montgomery_sqrt_cipolla(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_montgomery_sqrt_cipolla", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let a = square_input(&montgomery, &mut rng);
+    bencher.bench_local(move || sqrt::sqrt_cipolla(&montgomery, &a));
+}
+
+// Samples a base in the reducer's representation and an exponent with
+// exactly `exp_bits` bits
+fn pow_inputs<R: Reducer>(
+    reducer: &R,
+    exp_bits: usize,
+    rng: &mut StdRng,
+) -> (IntegerAU, IntegerAU) {
+    let base = reducer.to_internal(&random_below(reducer.modulus(), rng));
+    let top = &IntegerAU::from(1) << (exp_bits - 1);
+    let exp = &random_below(&top, rng) | &top;
+    (base, exp)
+}
+
+#[divan::bench(consts = EXPONENT_BITS, args = PRIMES)]
+fn bench_barrett_pow<const EXP_BITS: usize>(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_barrett_pow", format_args!("{prime_str}, {EXP_BITS}"));
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let barrett_reducer = Barrett::new(p);
+    let (base, exp) = pow_inputs(&barrett_reducer, EXP_BITS, &mut rng);
+    bencher.bench_local(move || barrett_reducer.pow(&base, &exp));
+}
+
+#[divan::bench(consts = EXPONENT_BITS, args = PRIMES)]
+fn bench_barrett_pow_square<const EXP_BITS: usize>(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng(
+        "bench_barrett_pow_square",
+        format_args!("{prime_str}, {EXP_BITS}"),
+    );
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let barrett_reducer = Barrett::new(p);
+    let (base, exp) = pow_inputs(&barrett_reducer, EXP_BITS, &mut rng);
+    bencher.bench_local(move || pow::pow_binary_square(&barrett_reducer, &base, &exp));
+}
+
+#[divan::bench(consts = EXPONENT_BITS, args = PRIMES)]
+fn bench_barrett_pow_fixed_window<const EXP_BITS: usize>(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng(
+        "bench_barrett_pow_fixed_window",
+        format_args!("{prime_str}, {EXP_BITS}"),
+    );
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let barrett_reducer = Barrett::new(p);
+    let (base, exp) = pow_inputs(&barrett_reducer, EXP_BITS, &mut rng);
+    bencher.bench_local(move || barrett_reducer.pow_fixed_window(&base, &exp, POW_WINDOW));
+}
+
+#[divan::bench(consts = EXPONENT_BITS, args = PRIMES)]
+fn bench_barrett_pow_sliding_window<const EXP_BITS: usize>(
+    bencher: divan::Bencher,
+    prime_str: &str,
+) {
+    let mut rng = bench_rng(
+        "bench_barrett_pow_sliding_window",
+        format_args!("{prime_str}, {EXP_BITS}"),
+    );
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let barrett_reducer = Barrett::new(p);
+    let (base, exp) = pow_inputs(&barrett_reducer, EXP_BITS, &mut rng);
+    bencher.bench_local(move || barrett_reducer.pow_sliding_window(&base, &exp, POW_WINDOW));
+}
+
+#[divan::bench(consts = EXPONENT_BITS, args = PRIMES)]
+fn bench_montgomery_pow<const EXP_BITS: usize>(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng(
+        "bench_montgomery_pow",
+        format_args!("{prime_str}, {EXP_BITS}"),
+    );
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let (base, exp) = pow_inputs(&montgomery, EXP_BITS, &mut rng);
+    bencher.bench_local(move || montgomery.to_canonical(&montgomery.pow(&base, &exp)));
+}
+
+#[divan::bench(consts = EXPONENT_BITS, args = PRIMES)]
+fn bench_montgomery_pow_square<const EXP_BITS: usize>(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng(
+        "bench_montgomery_pow_square",
+        format_args!("{prime_str}, {EXP_BITS}"),
+    );
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let (base, exp) = pow_inputs(&montgomery, EXP_BITS, &mut rng);
+    bencher.bench_local(move || {
+        montgomery.to_canonical(&pow::pow_binary_square(&montgomery, &base, &exp))
+    });
+}
+
+#[divan::bench(consts = EXPONENT_BITS, args = PRIMES)]
+fn bench_montgomery_pow_fixed_window<const EXP_BITS: usize>(
+    bencher: divan::Bencher,
+    prime_str: &str,
+) {
+    let mut rng = bench_rng(
+        "bench_montgomery_pow_fixed_window",
+        format_args!("{prime_str}, {EXP_BITS}"),
+    );
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let (base, exp) = pow_inputs(&montgomery, EXP_BITS, &mut rng);
+    bencher.bench_local(move || {
+        montgomery.to_canonical(&montgomery.pow_fixed_window(&base, &exp, POW_WINDOW))
+    });
+}
+
+#[divan::bench(consts = EXPONENT_BITS, args = PRIMES)]
+fn bench_montgomery_pow_sliding_window<const EXP_BITS: usize>(
+    bencher: divan::Bencher,
+    prime_str: &str,
+) {
+    let mut rng = bench_rng(
+        "bench_montgomery_pow_sliding_window",
+        format_args!("{prime_str}, {EXP_BITS}"),
+    );
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let (base, exp) = pow_inputs(&montgomery, EXP_BITS, &mut rng);
+    bencher.bench_local(move || {
+        montgomery.to_canonical(&montgomery.pow_sliding_window(&base, &exp, POW_WINDOW))
+    });
+}
+
+#[divan::bench(consts = EXPONENT_BITS, args = PRIMES)]
+fn bench_montgomery_pow_ladder<const EXP_BITS: usize>(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng(
+        "bench_montgomery_pow_ladder",
+        format_args!("{prime_str}, {EXP_BITS}"),
+    );
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let (base, exp) = pow_inputs(&montgomery, EXP_BITS, &mut rng);
+    bencher.bench_local(move || {
+        montgomery.to_canonical(&montgomery.pow_ladder(&base, &exp, EXP_BITS))
+    });
+}
+
+// Samples `count` non-zero values in the reducer's representation
+fn nonzero_inputs<R: Reducer>(reducer: &R, count: usize, rng: &mut StdRng) -> Vec<IntegerAU> {
+    let p_minus_one = reducer.modulus() - &IntegerAU::from(1);
+    (0..count)
+        .map(|_| {
+            let v = &random_below(&p_minus_one, rng) + &IntegerAU::from(1);
+            reducer.to_internal(&v)
+        })
+        .collect()
+}
+
+#[divan::bench(consts = BATCH_SIZES, args = PRIMES, max_time = 5)]
+fn bench_barrett_batch_invert<const N: usize>(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng(
+        "bench_barrett_batch_invert",
+        format_args!("{prime_str}, {N}"),
+    );
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let barrett_reducer = Barrett::new(p);
+    let values = nonzero_inputs(&barrett_reducer, N, &mut rng);
+    bencher
+        .with_inputs(|| values.clone())
+        .bench_local_refs(|values| barrett_reducer.batch_invert(values));
+}
+
+#[divan::bench(consts = BATCH_SIZES, args = PRIMES, max_time = 5)]
+fn bench_barrett_fermat_invert<const N: usize>(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng(
+        "bench_barrett_fermat_invert",
+        format_args!("{prime_str}, {N}"),
+    );
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let barrett_reducer = Barrett::new(p);
+    let values = nonzero_inputs(&barrett_reducer, N, &mut rng);
+    bencher.bench_local(|| {
+        values
             .iter()
-            .map(|(x, y)| (x.to_biguint(), y.to_biguint()))
-            .collect::<Vec<_>>();
-        let p_biguint = p.to_biguint();
-        let start = Instant::now();
-        let mut biguint_result = Vec::with_capacity(iterations);
-        for (x, y) in biguint_values {
-            // the value before reduction
-            let z = (x * y) % &p_biguint;
-            biguint_result.push(z);
-        }
-        let biguint_out = biguint_result
+            .map(|v| barrett_reducer.invert(v))
+            .collect::<Vec<_>>()
+    });
+}
+
+#[divan::bench(consts = BATCH_SIZES, args = PRIMES, max_time = 5)]
+fn bench_montgomery_batch_invert<const N: usize>(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng(
+        "bench_montgomery_batch_invert",
+        format_args!("{prime_str}, {N}"),
+    );
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let values = nonzero_inputs(&montgomery, N, &mut rng);
+    bencher
+        .with_inputs(|| values.clone())
+        .bench_local_refs(|values| montgomery.batch_invert(values));
+}
+
+#[divan::bench(consts = BATCH_SIZES, args = PRIMES, max_time = 5)]
+fn bench_montgomery_fermat_invert<const N: usize>(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng(
+        "bench_montgomery_fermat_invert",
+        format_args!("{prime_str}, {N}"),
+    );
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let values = nonzero_inputs(&montgomery, N, &mut rng);
+    bencher.bench_local(|| {
+        values
             .iter()
-            .fold(BigUint::from(0u64), |acc, x| (&acc + x) % &p_biguint);
-        println!(
-            "BigUint time for {iterations} multiplications and summation: {:?}",
-            start.elapsed()
-        );
-        for i in 0..iterations {
-            assert_eq!(
-                expected_out, barrett_out,
-                "barrett reduction mismatches naive reduction"
-            );
-            assert_eq!(
-                expected_out, mont_out,
-                "montgomery reduction mismatches naive reduction"
-            );
-            assert_eq!(
-                expected_out,
-                IntegerAU::from_biguint(biguint_out.clone()),
-                "BigUint reduction mismatches naive reduction"
-            );
-        }
-    }
-    Ok(())
+            .map(|v| montgomery.invert(v))
+            .collect::<Vec<_>>()
+    });
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_fermat_invert(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_fermat_invert", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let x = &nonzero_inputs(&montgomery, 1, &mut rng)[0];
+    bencher.bench_local(move || montgomery.invert(x));
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_safegcd_invert(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_safegcd_invert", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let x = &nonzero_inputs(&montgomery, 1, &mut rng)[0];
+    bencher.bench_local(move || montgomery.invert_safegcd(x));
+}
+
+#[divan::bench(args = PRIMES)]
+fn bench_bingcd_invert(bencher: divan::Bencher, prime_str: &str) {
+    let mut rng = bench_rng("bench_bingcd_invert", prime_str);
+    let p = IntegerAU::from_str(prime_str).unwrap();
+    let montgomery = Montgomery::new(&p);
+    let x = &nonzero_inputs(&montgomery, 1, &mut rng)[0];
+    bencher.bench_local(move || montgomery.invert_bingcd(x));
 }
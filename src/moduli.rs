@@ -0,0 +1,89 @@
+use std::str::FromStr;
+
+use super::integer_au::IntegerAU;
+
+/// Well known primes that can be referred to by name
+pub static NAMED_MODULI: [(&str, &str); 12] = [
+    ("mersenne31", "2147483647"),
+    ("babybear", "2013265921"),
+    ("koalabear", "2130706433"),
+    ("goldilocks", "18446744069414584321"),
+    ("mersenne127", "170141183460469231731687303715884105727"),
+    (
+        "bn254",
+        "21888242871839275222246405745257275088696311157297823662689037894645226208583",
+    ),
+    (
+        "bn254-scalar",
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    ),
+    (
+        "bls12-381",
+        "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787",
+    ),
+    (
+        "bls12-381-scalar",
+        "52435875175126190479447740508185965837690552500527637822603658699938581184513",
+    ),
+    (
+        "p256",
+        "115792089210356248762697446949407573530086143415290314195533631308867097853951",
+    ),
+    (
+        "secp256k1",
+        "115792089237316195423570985008687907853269984665640564039457584007908834671663",
+    ),
+    (
+        "ed25519",
+        "57896044618658097711785492504343953926634992332820282019728792003956564819949",
+    ),
+];
+
+/// Parses a modulus given by name, or as a number in any format accepted by
+/// `IntegerAU::from_str`
+pub fn parse_modulus(s: &str) -> Result<IntegerAU, String> {
+    let s = s.trim();
+    if let Some((_, value)) = NAMED_MODULI
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(s))
+    {
+        return Ok(IntegerAU::from_str(value).unwrap());
+    }
+    let modulus = IntegerAU::from_str(s).map_err(|e| {
+        let names = NAMED_MODULI
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "invalid modulus \"{s}\" ({e}), expected a decimal, 0x hex, 0b binary or 0o octal \
+             number or one of: {names}"
+        )
+    })?;
+    if modulus < IntegerAU::from(3) || !modulus.bit(0) {
+        return Err(format!("modulus {s} must be an odd number greater than 2"));
+    }
+    Ok(modulus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PRIMES;
+
+    #[test]
+    fn test_parse_modulus() {
+        let goldilocks = IntegerAU::from_str(PRIMES[1]).unwrap();
+        assert_eq!(parse_modulus("goldilocks").unwrap(), goldilocks);
+        assert_eq!(parse_modulus("Goldilocks").unwrap(), goldilocks);
+        assert_eq!(parse_modulus(PRIMES[1]).unwrap(), goldilocks);
+        assert_eq!(parse_modulus("0xffffffff00000001").unwrap(), goldilocks);
+        assert_eq!(parse_modulus("0XFFFFFFFF00000001").unwrap(), goldilocks);
+        assert!(parse_modulus("goldilock").is_err());
+        assert!(parse_modulus("0x").is_err());
+        assert!(parse_modulus("10").is_err());
+        assert!(parse_modulus("1").is_err());
+        assert_eq!(parse_modulus("0b111").unwrap(), IntegerAU::from(7));
+        assert_eq!(parse_modulus("0o17").unwrap(), IntegerAU::from(15));
+    }
+}
@@ -1,5 +1,14 @@
+use super::ct;
+use super::op_count;
+use super::reducer::Reducer;
+use super::reducer::Scratch;
 use super::IntegerAU;
 
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "MontgomeryParams")
+)]
 pub struct Montgomery {
     r: IntegerAU,
     r_bitmask: IntegerAU,
@@ -8,10 +17,55 @@ pub struct Montgomery {
     prime: IntegerAU,
 }
 
+// Deserialized parameters, checked before they become a Montgomery
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct MontgomeryParams {
+    r: IntegerAU,
+    r_bitmask: IntegerAU,
+    r_bits: usize,
+    n_prime: IntegerAU,
+    prime: IntegerAU,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<MontgomeryParams> for Montgomery {
+    type Error = String;
+
+    fn try_from(params: MontgomeryParams) -> Result<Self, String> {
+        let MontgomeryParams {
+            r,
+            r_bitmask,
+            r_bits,
+            n_prime,
+            prime,
+        } = params;
+        if !prime.bit(0) || prime < IntegerAU::from(3) || r_bits != prime.bit_len() {
+            return Err(format!(
+                "invalid Montgomery modulus {prime} of {r_bits} bits"
+            ));
+        }
+        if r != &IntegerAU::from(1) << r_bits || r_bitmask != &r - &IntegerAU::from(1) {
+            return Err(format!("invalid Montgomery R for modulus {prime}"));
+        }
+        // n' = -p^-1 mod R iff p n' = -1 mod R
+        if n_prime >= r || &(&prime * &n_prime) & &r_bitmask != r_bitmask {
+            return Err(format!("invalid Montgomery n' for modulus {prime}"));
+        }
+        Ok(Self {
+            r,
+            r_bitmask,
+            r_bits,
+            n_prime,
+            prime,
+        })
+    }
+}
+
 impl Montgomery {
     pub fn new(prime: &IntegerAU) -> Self {
         let r = &IntegerAU::from(1) << prime.bit_len();
-        let r_minus_prime = &r - &prime;
+        let r_minus_prime = &r - prime;
         let n_prime =
             IntegerAU::from_biguint(r_minus_prime.to_biguint().modinv(&r.to_biguint()).unwrap());
         // let n_prime = (prime_inv_r + r.clone()) % r.clone();
@@ -36,11 +90,13 @@ impl Montgomery {
         (v << self.r_bits) % self.prime.clone()
     }
 
+    #[allow(clippy::wrong_self_convention)]
     pub fn from_mont(&self, v: &IntegerAU) -> IntegerAU {
         self.redc(v)
     }
 
     pub fn redc(&self, v: &IntegerAU) -> IntegerAU {
+        op_count::record_reduction();
         let t = &(v + &(&(&(&(v & &self.r_bitmask) * &self.n_prime) & &self.r_bitmask)
             * &self.prime))
             >> self.r_bits;
@@ -50,4 +106,212 @@ impl Montgomery {
             t
         }
     }
+
+    /// Same as `redc`, writing the result into `out` and keeping the
+    /// intermediates in `scratch` instead of allocating them
+    pub fn redc_into(&self, v: &IntegerAU, out: &mut IntegerAU, scratch: &mut Scratch) {
+        self.redc_with(v, out, &mut scratch.a, &mut scratch.b);
+    }
+
+    fn redc_with(&self, v: &IntegerAU, out: &mut IntegerAU, m: &mut IntegerAU, t: &mut IntegerAU) {
+        op_count::record_reduction();
+        m.clone_from(v);
+        *m &= &self.r_bitmask;
+        IntegerAU::mul_into(m, &self.n_prime, t);
+        *t &= &self.r_bitmask;
+        IntegerAU::mul_into(t, &self.prime, out);
+        *out += v;
+        *out >>= self.r_bits;
+        if *out >= self.prime {
+            *out -= &self.prime;
+        }
+    }
+
+    /// Montgomery reduction on fixed width limbs with a branch-free final
+    /// subtraction. Every intermediate has a width that only depends on the
+    /// modulus and the conditional subtraction of p is always computed, the
+    /// result being picked with a mask. Input must be less than p * R.
+    pub fn redc_ct(&self, v: &IntegerAU) -> IntegerAU {
+        let width = self.prime.limbs.len();
+        IntegerAU {
+            limbs: self.redc_ct_limbs(&ct::pad(v.limbs.clone(), 2 * width)),
+        }
+        .trim()
+    }
+
+    // Reduces a 2n limb value into n limbs where n is the limb count of p
+    fn redc_ct_limbs(&self, v: &[u64]) -> Vec<u64> {
+        let width = self.prime.limbs.len();
+        let mask = ct::pad(self.r_bitmask.limbs.clone(), width);
+        let m = ct::and(&ct::mul(&ct::and(v, &mask), &self.n_prime.limbs), &mask);
+        let sum = ct::add(v, &ct::mul(&m, &self.prime.limbs));
+        // t < 2p so one extra limb is enough to hold it
+        let t = ct::shr(&sum, self.r_bits, width + 1);
+        let (diff, borrow) = ct::sub_borrow(&t, &self.prime.limbs);
+        let mut result = ct::select(ct::mask(borrow ^ 1), &diff, &t);
+        result.truncate(width);
+        result
+    }
+
+    /// Computes base^exp with a Montgomery ladder
+    /// Exactly `exp_bits` iterations are performed, each doing one
+    /// multiplication and one squaring regardless of the exponent bit.
+    /// The base is expected in Montgomery form and the result is returned
+    /// in Montgomery form.
+    pub fn pow_ladder(&self, base: &IntegerAU, exp: &IntegerAU, exp_bits: usize) -> IntegerAU {
+        assert!(
+            exp.bit_len() <= exp_bits,
+            "exponent does not fit in {exp_bits} bits"
+        );
+        let width = self.prime.limbs.len();
+        let mut r0 = ct::pad(self.one().limbs, width);
+        let mut r1 = ct::pad(base.limbs.clone(), width);
+        for i in (0..exp_bits).rev() {
+            let swap = ct::mask(exp.bit(i) as u64);
+            ct::cswap(swap, &mut r0, &mut r1);
+            let product = self.redc_ct_limbs(&ct::mul(&r0, &r1));
+            r0 = self.redc_ct_limbs(&ct::mul(&r0, &r0));
+            r1 = product;
+            ct::cswap(swap, &mut r0, &mut r1);
+        }
+        IntegerAU { limbs: r0 }.trim()
+    }
+}
+
+impl Reducer for Montgomery {
+    fn modulus(&self) -> &IntegerAU {
+        &self.prime
+    }
+
+    fn to_internal(&self, v: &IntegerAU) -> IntegerAU {
+        self.to_mont(v)
+    }
+
+    fn to_canonical(&self, v: &IntegerAU) -> IntegerAU {
+        self.from_mont(v)
+    }
+
+    fn one(&self) -> IntegerAU {
+        self.r.modulo(&self.prime).unwrap()
+    }
+
+    fn mul(&self, a: &IntegerAU, b: &IntegerAU) -> IntegerAU {
+        self.redc(&op_count::uncounted(|| a * b))
+    }
+
+    fn square(&self, a: &IntegerAU) -> IntegerAU {
+        self.redc(&op_count::uncounted(|| a.square()))
+    }
+
+    fn mul_into(&self, a: &IntegerAU, b: &IntegerAU, out: &mut IntegerAU, scratch: &mut Scratch) {
+        let Scratch {
+            product,
+            a: m,
+            b: t,
+        } = scratch;
+        op_count::uncounted(|| IntegerAU::mul_into(a, b, product));
+        self.redc_with(product, out, m, t);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::test_rng;
+    use crate::PRIMES;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_redc_ct() {
+        let mut rng = test_rng();
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            let montgomery = Montgomery::new(&p);
+            for _ in 0..100 {
+                let x = IntegerAU::random_below_with(&p, &mut rng);
+                let y = IntegerAU::random_below_with(&p, &mut rng);
+                let z = &x * &y;
+                assert_eq!(
+                    montgomery.redc_ct(&z),
+                    montgomery.redc(&z),
+                    "Failed redc_ct test: {} * {} mod {}",
+                    x,
+                    y,
+                    p
+                );
+            }
+            // values just below p * R
+            let z = &(&p * &montgomery.r) - &IntegerAU::from(1);
+            assert_eq!(montgomery.redc_ct(&z), montgomery.redc(&z));
+        }
+    }
+
+    #[test]
+    fn test_redc_into() {
+        let mut rng = test_rng();
+        let mut scratch = Scratch::new();
+        let mut out = IntegerAU::from(0);
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            let montgomery = Montgomery::new(&p);
+            for _ in 0..100 {
+                let x = IntegerAU::random_below_with(&p, &mut rng);
+                let y = IntegerAU::random_below_with(&p, &mut rng);
+                let z = &x * &y;
+                montgomery.redc_into(&z, &mut out, &mut scratch);
+                assert_eq!(
+                    out,
+                    montgomery.redc(&z),
+                    "Failed redc_into test: {z} mod {p}"
+                );
+                montgomery.mul_into(&x, &y, &mut out, &mut scratch);
+                assert_eq!(out, montgomery.mul(&x, &y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_pow_ladder() {
+        let mut rng = test_rng();
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            let montgomery = Montgomery::new(&p);
+            for exp_bits in [1, 64, 300] {
+                let base = IntegerAU::random_below_with(&p, &mut rng);
+                let exp =
+                    IntegerAU::random_below_with(&(&IntegerAU::from(1) << exp_bits), &mut rng);
+                let expected = base.to_biguint().modpow(&exp.to_biguint(), &p.to_biguint());
+                let result = montgomery.pow_ladder(&montgomery.to_mont(&base), &exp, exp_bits);
+                assert_eq!(
+                    montgomery.from_mont(&result).to_biguint(),
+                    expected,
+                    "Failed ladder test: {}^{} mod {}",
+                    base,
+                    exp,
+                    p
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            let montgomery = Montgomery::new(&p);
+            let x = montgomery.to_mont(&(&p - &IntegerAU::from(1)));
+            let json = serde_json::to_string(&montgomery).unwrap();
+            let loaded: Montgomery = serde_json::from_str(&json).unwrap();
+            assert_eq!(loaded.mul(&x, &x), montgomery.mul(&x, &x));
+            let bytes = bincode::serialize(&montgomery).unwrap();
+            let loaded: Montgomery = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(loaded.n_prime, montgomery.n_prime);
+
+            let mut tampered = montgomery;
+            tampered.n_prime += &IntegerAU::from(2);
+            let json = serde_json::to_string(&tampered).unwrap();
+            assert!(serde_json::from_str::<Montgomery>(&json).is_err());
+        }
+    }
 }
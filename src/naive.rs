@@ -0,0 +1,46 @@
+use super::integer_au::IntegerAU;
+use super::op_count;
+use super::reducer::Reducer;
+
+/// Reduces every product with the generic IntegerAU modulo
+pub struct Naive {
+    prime: IntegerAU,
+}
+
+impl Naive {
+    pub fn new(prime: IntegerAU) -> Self {
+        Self { prime }
+    }
+}
+
+impl Reducer for Naive {
+    fn modulus(&self) -> &IntegerAU {
+        &self.prime
+    }
+
+    fn to_internal(&self, v: &IntegerAU) -> IntegerAU {
+        v.clone()
+    }
+
+    fn to_canonical(&self, v: &IntegerAU) -> IntegerAU {
+        v.clone()
+    }
+
+    fn canonicalize(&self, v: IntegerAU) -> IntegerAU {
+        v
+    }
+
+    fn one(&self) -> IntegerAU {
+        IntegerAU::from(1)
+    }
+
+    fn mul(&self, a: &IntegerAU, b: &IntegerAU) -> IntegerAU {
+        op_count::record_reduction();
+        op_count::uncounted(|| a * b) % self.prime.clone()
+    }
+
+    fn square(&self, a: &IntegerAU) -> IntegerAU {
+        op_count::record_reduction();
+        op_count::uncounted(|| a.square()) % self.prime.clone()
+    }
+}
@@ -0,0 +1,226 @@
+//! Counters of the work done by `IntegerAU` arithmetic
+//!
+//! Only active with the `op-count` cargo feature, otherwise recording
+//! compiles to nothing and every count reads as zero. Counts are kept per
+//! thread. The reducers record each modular reduction and leave the product
+//! being reduced uncounted, so the counts describe the reduction work and
+//! are normalized per reduction. Counting slows down the arithmetic it
+//! observes, so timings taken with the feature enabled aren't comparable
+//! with timings taken without it.
+
+use std::ops::Sub;
+
+/// Whether the `op-count` feature is enabled
+pub const ENABLED: bool = cfg!(feature = "op-count");
+
+/// Work done by IntegerAU arithmetic
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpCounts {
+    /// 64 x 64 bit limb products
+    pub limb_muls: u64,
+    /// Limb additions and subtractions, including multiply-accumulates
+    pub limb_adds: u64,
+    /// Heap allocations of limb vectors
+    pub allocations: u64,
+    /// IntegerAU operations doing limb work, e.g. one `&a * &b`
+    pub integer_ops: u64,
+    /// Modular reductions performed by the reducers
+    pub reductions: u64,
+}
+
+impl OpCounts {
+    /// Limb muls, limb adds, allocations and integer ops divided by the
+    /// number of reductions they were counted over
+    pub fn per_reduction(&self) -> [f64; 4] {
+        let n = self.reductions.max(1) as f64;
+        [
+            self.limb_muls as f64 / n,
+            self.limb_adds as f64 / n,
+            self.allocations as f64 / n,
+            self.integer_ops as f64 / n,
+        ]
+    }
+}
+
+impl Sub for OpCounts {
+    type Output = OpCounts;
+
+    fn sub(self, other: OpCounts) -> OpCounts {
+        OpCounts {
+            limb_muls: self.limb_muls - other.limb_muls,
+            limb_adds: self.limb_adds - other.limb_adds,
+            allocations: self.allocations - other.allocations,
+            integer_ops: self.integer_ops - other.integer_ops,
+            reductions: self.reductions - other.reductions,
+        }
+    }
+}
+
+#[cfg(feature = "op-count")]
+thread_local! {
+    static COUNTS: std::cell::Cell<OpCounts> = const {
+        std::cell::Cell::new(OpCounts {
+            limb_muls: 0,
+            limb_adds: 0,
+            allocations: 0,
+            integer_ops: 0,
+            reductions: 0,
+        })
+    };
+}
+
+/// Records one IntegerAU operation and the limb work it did, operations
+/// without limb work only count their allocations
+#[inline(always)]
+#[allow(unused_variables)]
+pub(crate) fn record(limb_muls: usize, limb_adds: usize, allocations: usize) {
+    #[cfg(feature = "op-count")]
+    COUNTS.with(|counts| {
+        let mut c = counts.get();
+        c.limb_muls += limb_muls as u64;
+        c.limb_adds += limb_adds as u64;
+        c.allocations += allocations as u64;
+        c.integer_ops += (limb_muls + limb_adds > 0) as u64;
+        counts.set(c);
+    });
+}
+
+/// Records one modular reduction
+#[inline(always)]
+pub(crate) fn record_reduction() {
+    #[cfg(feature = "op-count")]
+    COUNTS.with(|counts| {
+        let mut c = counts.get();
+        c.reductions += 1;
+        counts.set(c);
+    });
+}
+
+/// Runs `f` without counting the work it does, for the products the
+/// reducers reduce
+#[inline(always)]
+pub(crate) fn uncounted<T>(f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "op-count")]
+    {
+        let before = snapshot();
+        let output = f();
+        COUNTS.with(|counts| counts.set(before));
+        output
+    }
+    #[cfg(not(feature = "op-count"))]
+    f()
+}
+
+/// Counts recorded on this thread so far
+pub fn snapshot() -> OpCounts {
+    #[cfg(feature = "op-count")]
+    return COUNTS.with(|counts| counts.get());
+    #[cfg(not(feature = "op-count"))]
+    OpCounts::default()
+}
+
+/// Runs `f` and returns the work it did on this thread
+pub fn count<T>(f: impl FnOnce() -> T) -> (T, OpCounts) {
+    let before = snapshot();
+    let output = f();
+    (output, snapshot() - before)
+}
+
+#[cfg(all(test, feature = "op-count"))]
+mod tests {
+    use super::*;
+    use crate::barrett::Barrett;
+    use crate::integer_au::IntegerAU;
+    use crate::montgomery::Montgomery;
+    use crate::reducer::Reducer;
+    use crate::reducer::Scratch;
+
+    #[test]
+    fn test_counts() {
+        let a = IntegerAU {
+            limbs: vec![1, 2, 3],
+        };
+        let b = IntegerAU { limbs: vec![4, 5] };
+        let (_, counts) = count(|| &a * &b);
+        assert_eq!(
+            counts,
+            OpCounts {
+                limb_muls: 6,
+                limb_adds: 6,
+                allocations: 1,
+                integer_ops: 1,
+                reductions: 0,
+            }
+        );
+        let (_, counts) = count(|| &a + &b);
+        assert_eq!(counts.limb_adds, 3);
+        assert_eq!(counts.limb_muls, 0);
+        // 3 cross products and 3 squares instead of 9 products
+        let (_, counts) = count(|| a.square());
+        assert_eq!(counts.limb_muls, 6);
+        // shifts and masks do no limb arithmetic
+        let (_, counts) = count(|| {
+            let mut c = a.clone();
+            c >>= 3;
+            c <<= 3;
+            c &= &b;
+            c |= &b;
+            c ^= &b;
+        });
+        assert_eq!(counts.integer_ops, 0);
+    }
+
+    #[test]
+    fn test_reduction_counts() {
+        // 2^255 - 19, 4 limbs
+        let p = &(&IntegerAU::from(1) << 255) - &IntegerAU::from(19);
+        let x = &(&p - &IntegerAU::from(1)) * &(&p - &IntegerAU::from(2));
+        let barrett = Barrett::new(p.clone());
+        let montgomery = Montgomery::new(&p);
+        let (_, barrett_counts) = count(|| barrett.reduce(&x));
+        let (_, redc_counts) = count(|| montgomery.redc(&x));
+        // a 4 x 4 limb product at least
+        assert!(barrett_counts.limb_muls >= 16, "{barrett_counts:?}");
+        assert!(redc_counts.limb_muls >= 16, "{redc_counts:?}");
+        assert_eq!(barrett_counts.reductions, 1);
+        assert_eq!(redc_counts.reductions, 1);
+        let (_, modulo_counts) = count(|| x.modulo(&p));
+        assert_eq!(modulo_counts.limb_muls, 0);
+        assert!(modulo_counts.integer_ops > 0);
+        // the product is left out of a reducer's multiplication
+        let a = &p - &IntegerAU::from(1);
+        let b = &p - &IntegerAU::from(2);
+        let (_, mul_counts) = count(|| barrett.mul(&a, &b));
+        assert_eq!(mul_counts, barrett_counts);
+        let (_, mul_counts) = count(|| montgomery.mul(&a, &b));
+        assert_eq!(mul_counts, redc_counts);
+    }
+
+    #[test]
+    fn test_mul_into_allocations() {
+        let p = &(&IntegerAU::from(1) << 255) - &IntegerAU::from(19);
+        let a = &p - &IntegerAU::from(3);
+        let b = &p - &IntegerAU::from(5);
+        let barrett = Barrett::new(p.clone());
+        let montgomery = Montgomery::new(&p);
+        for reducer in [&barrett as &dyn Reducer, &montgomery] {
+            let mut scratch = Scratch::new();
+            let mut out = IntegerAU::from(0);
+            let mut acc = reducer.to_internal(&a);
+            let mut round = |acc: &mut IntegerAU, out: &mut IntegerAU| {
+                reducer.mul_into(acc, &b, out, &mut scratch);
+                std::mem::swap(acc, out);
+            };
+            // the first rounds grow the buffers
+            round(&mut acc, &mut out);
+            round(&mut acc, &mut out);
+            let (_, counts) = count(|| {
+                for _ in 0..100 {
+                    round(&mut acc, &mut out);
+                }
+            });
+            assert_eq!(counts.allocations, 0, "{counts:?}");
+            assert!(counts.limb_muls > 0);
+        }
+    }
+}
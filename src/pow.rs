@@ -0,0 +1,196 @@
+use super::integer_au::IntegerAU;
+use super::reducer::Reducer;
+
+/// Largest window size accepted by the windowed exponentiations. The
+/// precomputed table has up to 2^window entries.
+pub const MAX_WINDOW: usize = 16;
+
+/// Left-to-right square-and-multiply
+/// One squaring per exponent bit and one multiplication per set bit
+pub fn pow_binary<R: Reducer + ?Sized>(
+    reducer: &R,
+    base: &IntegerAU,
+    exp: &IntegerAU,
+) -> IntegerAU {
+    square_and_multiply(reducer, base, exp, |x| reducer.mul(x, x))
+}
+
+/// Same as `pow_binary` with the squarings done by `Reducer::square`, to
+/// compare a dedicated squaring against squaring through `mul`
+pub fn pow_binary_square<R: Reducer + ?Sized>(
+    reducer: &R,
+    base: &IntegerAU,
+    exp: &IntegerAU,
+) -> IntegerAU {
+    square_and_multiply(reducer, base, exp, |x| reducer.square(x))
+}
+
+// Left-to-right square-and-multiply with the given squaring
+fn square_and_multiply<R: Reducer + ?Sized>(
+    reducer: &R,
+    base: &IntegerAU,
+    exp: &IntegerAU,
+    square: impl Fn(&IntegerAU) -> IntegerAU,
+) -> IntegerAU {
+    let mut result = reducer.one();
+    for i in (0..exp.bit_len()).rev() {
+        result = square(&result);
+        if exp.bit(i) {
+            result = reducer.mul(&result, base);
+        }
+    }
+    result
+}
+
+/// Fixed window (k-ary) exponentiation
+/// Precomputes base^0 .. base^(2^window - 1) then consumes the exponent
+/// in window sized digits from the most significant end
+pub fn pow_fixed_window<R: Reducer + ?Sized>(
+    reducer: &R,
+    base: &IntegerAU,
+    exp: &IntegerAU,
+    window: usize,
+) -> IntegerAU {
+    assert!(
+        (1..=MAX_WINDOW).contains(&window),
+        "window must be between 1 and {MAX_WINDOW}"
+    );
+    let mut table = Vec::with_capacity(1 << window);
+    table.push(reducer.one());
+    for i in 1..(1 << window) {
+        table.push(reducer.mul(&table[i - 1], base));
+    }
+
+    let mut result = reducer.one();
+    for digit in exp.windows(window).rev() {
+        for _ in 0..window {
+            result = reducer.mul(&result, &result);
+        }
+        if digit != 0 {
+            result = reducer.mul(&result, &table[digit as usize]);
+        }
+    }
+    result
+}
+
+/// Sliding window exponentiation
+/// Only odd powers base^1, base^3, .. base^(2^window - 1) are precomputed.
+/// Zero bits are handled with a single squaring and every window starts
+/// and ends on a set bit.
+pub fn pow_sliding_window<R: Reducer + ?Sized>(
+    reducer: &R,
+    base: &IntegerAU,
+    exp: &IntegerAU,
+    window: usize,
+) -> IntegerAU {
+    assert!(
+        (1..=MAX_WINDOW).contains(&window),
+        "window must be between 1 and {MAX_WINDOW}"
+    );
+    let base_sq = reducer.mul(base, base);
+    let mut odd_powers = Vec::with_capacity(1 << (window - 1));
+    odd_powers.push(base.clone());
+    for i in 1..(1 << (window - 1)) {
+        odd_powers.push(reducer.mul(&odd_powers[i - 1], &base_sq));
+    }
+
+    let mut result = reducer.one();
+    let mut i = exp.bit_len();
+    while i > 0 {
+        if !exp.bit(i - 1) {
+            result = reducer.mul(&result, &result);
+            i -= 1;
+            continue;
+        }
+        // find the lowest set bit that still fits in the window
+        let mut low = i.saturating_sub(window);
+        while !exp.bit(low) {
+            low += 1;
+        }
+        let width = i - low;
+        for _ in 0..width {
+            result = reducer.mul(&result, &result);
+        }
+        let digit = exp.bits_at(low, width) as usize;
+        result = reducer.mul(&result, &odd_powers[digit >> 1]);
+        i = low;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::barrett::Barrett;
+    use crate::montgomery::Montgomery;
+    use crate::seed::test_rng;
+    use crate::PRIMES;
+    use std::str::FromStr;
+
+    fn check_pow<R: Reducer>(reducer: &R, p: &IntegerAU) {
+        let mut rng = test_rng();
+        let p_big = p.to_biguint();
+        let mut exps = vec![
+            IntegerAU::from(0),
+            IntegerAU::from(1),
+            IntegerAU::from(2),
+            p - &IntegerAU::from(1),
+        ];
+        for bits in [64, 255, 512] {
+            exps.push(IntegerAU::random_below_with(
+                &(&IntegerAU::from(1) << bits),
+                &mut rng,
+            ));
+        }
+        for exp in &exps {
+            let base = IntegerAU::random_below_with(p, &mut rng);
+            let expected = base.to_biguint().modpow(&exp.to_biguint(), &p_big);
+            let base_internal = reducer.to_internal(&base);
+
+            let results = [
+                ("binary", reducer.pow(&base_internal, exp)),
+                (
+                    "binary square",
+                    pow_binary_square(reducer, &base_internal, exp),
+                ),
+                ("fixed 1", reducer.pow_fixed_window(&base_internal, exp, 1)),
+                ("fixed 4", reducer.pow_fixed_window(&base_internal, exp, 4)),
+                (
+                    "sliding 1",
+                    reducer.pow_sliding_window(&base_internal, exp, 1),
+                ),
+                (
+                    "sliding 5",
+                    reducer.pow_sliding_window(&base_internal, exp, 5),
+                ),
+            ];
+            for (name, result) in results {
+                assert_eq!(
+                    reducer.to_canonical(&result).to_biguint(),
+                    expected,
+                    "Failed {} pow test: {}^{} mod {}",
+                    name,
+                    base,
+                    exp,
+                    p
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_pow_barrett() {
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            check_pow(&Barrett::new(p.clone()), &p);
+        }
+    }
+
+    #[test]
+    fn test_pow_montgomery() {
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            check_pow(&Montgomery::new(&p), &p);
+        }
+    }
+}
@@ -0,0 +1,659 @@
+//! Probabilistic primality testing and random prime generation
+//!
+//! Exponentiations use the crate's own Montgomery arithmetic so generating
+//! large primes exercises the same code paths as the benchmarks.
+
+use rand::Rng;
+
+use super::integer_au::IntegerAU;
+use super::montgomery::Montgomery;
+use super::reducer::Reducer;
+use super::seed;
+
+/// Miller-Rabin rounds used when generating primes, a composite passes with
+/// probability below 4^-40
+pub const MR_ROUNDS: usize = 40;
+
+// Candidates divisible by one of these are rejected before running
+// Miller-Rabin
+const SMALL_PRIMES: [u64; 53] = [
+    3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251,
+];
+
+/// Which probabilistic primality test `IntegerAU::is_probable_prime` runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrimalityTest {
+    /// Miller-Rabin with this many random bases, a composite passes with
+    /// probability at most 4^-rounds
+    MillerRabin(usize),
+    /// Baillie-PSW: Miller-Rabin to base 2 followed by a strong Lucas test
+    /// with Selfridge's parameters. Deterministic, with no known
+    /// counterexample.
+    BailliePsw,
+}
+
+impl IntegerAU {
+    /// Tests whether the value is prime, probabilistically for
+    /// `PrimalityTest::MillerRabin`
+    /// Primes always pass.
+    pub fn is_probable_prime(&self, test: PrimalityTest) -> bool {
+        match test {
+            PrimalityTest::MillerRabin(rounds) => {
+                seed::with_thread_rng(|rng| miller_rabin(self, rounds, rng))
+            }
+            PrimalityTest::BailliePsw => baillie_psw(self),
+        }
+    }
+}
+
+/// Miller-Rabin test with `rounds` random bases
+/// Primes always pass, a composite passes with probability at most
+/// 4^-rounds.
+pub fn miller_rabin<R: Rng + ?Sized>(n: &IntegerAU, rounds: usize, rng: &mut R) -> bool {
+    if let Some(small) = trivial_primality(n) {
+        return small;
+    }
+    let two = IntegerAU::from(2);
+    let montgomery = Montgomery::new(n);
+    // bases are drawn from [2, n - 2]
+    let base_range = n - &IntegerAU::from(3);
+    (0..rounds).all(|_| {
+        let a = if base_range.is_zero() {
+            two.clone()
+        } else {
+            &IntegerAU::random_below_with(&base_range, rng) + &two
+        };
+        strong_probable_prime(&montgomery, &a)
+    })
+}
+
+/// Baillie-PSW primality test, a strong probable prime test to base 2 and a
+/// strong Lucas probable prime test
+pub fn baillie_psw(n: &IntegerAU) -> bool {
+    if let Some(small) = trivial_primality(n) {
+        return small;
+    }
+    let montgomery = Montgomery::new(n);
+    strong_probable_prime(&montgomery, &IntegerAU::from(2)) && strong_lucas(&montgomery)
+}
+
+// Answers for values below 4, even values and multiples of small primes,
+// None if n needs a full test
+fn trivial_primality(n: &IntegerAU) -> Option<bool> {
+    if n.limbs.len() == 1 && n.limbs[0] < 4 {
+        return Some(n.limbs[0] >= 2);
+    }
+    if !n.bit(0) {
+        return Some(false);
+    }
+    for &p in &SMALL_PRIMES {
+        if n.limbs.len() == 1 && n.limbs[0] == p {
+            return Some(true);
+        }
+        if rem_u64(n, p) == 0 {
+            return Some(false);
+        }
+    }
+    None
+}
+
+// Miller-Rabin round to base a for the modulus of `montgomery`, which must
+// be odd and greater than a
+fn strong_probable_prime(montgomery: &Montgomery, a: &IntegerAU) -> bool {
+    let n = montgomery.modulus();
+    let n_minus_one = n - &IntegerAU::from(1);
+    // n - 1 = d * 2^s with d odd
+    let s = n_minus_one.trailing_zeros().unwrap();
+    let d = &n_minus_one >> s;
+    let one = montgomery.one();
+    let minus_one = montgomery.to_internal(&n_minus_one);
+    let mut x = montgomery.pow(&montgomery.to_internal(a), &d);
+    if x == one || x == minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = montgomery.mul(&x, &x);
+        if x == minus_one {
+            return true;
+        }
+    }
+    false
+}
+
+// Strong Lucas probable prime test with Selfridge's method A parameters:
+// the first D in 5, -7, 9, -11, ... with Jacobi symbol (D/n) = -1, P = 1
+// and Q = (1 - D) / 4. Lucas sequences are computed on Montgomery form
+// values, halving and additions are linear so work unchanged on them.
+fn strong_lucas(montgomery: &Montgomery) -> bool {
+    let n = montgomery.modulus();
+    // no D exists for perfect squares
+    if n.is_perfect_square() {
+        return false;
+    }
+    let mut d_abs = 5u64;
+    let mut negative = false;
+    loop {
+        let d_mod_n = signed_mod(d_abs, negative, n);
+        match jacobi(&d_mod_n, n) {
+            -1 => break,
+            // shares a factor with n, which is bigger than every small D
+            // since trivial_primality ruled out small factors
+            0 if &IntegerAU::from(d_abs) != n => return false,
+            _ => {}
+        }
+        d_abs += 2;
+        negative = !negative;
+    }
+    // Q = (1 - D) / 4
+    let (q_abs, q_negative) = if negative {
+        ((1 + d_abs) / 4, false)
+    } else {
+        ((d_abs - 1) / 4, true)
+    };
+    let to_m = |v: &IntegerAU| montgomery.to_internal(v);
+    let d_m = to_m(&signed_mod(d_abs, negative, n));
+    let q_m = to_m(&signed_mod(q_abs, q_negative, n));
+    let zero = IntegerAU::from(0);
+
+    // n + 1 = d * 2^s with d odd
+    let n_plus_one = n + &IntegerAU::from(1);
+    let s = n_plus_one.trailing_zeros().unwrap();
+    let d = &n_plus_one >> s;
+
+    // U_1 = 1, V_1 = P = 1, Q^1
+    let mut u = montgomery.one();
+    let mut v = montgomery.one();
+    let mut qk = q_m.clone();
+    for i in (0..d.bit_len() - 1).rev() {
+        // U_2k = U_k V_k, V_2k = V_k^2 - 2 Q^k
+        u = montgomery.mul(&u, &v);
+        v = montgomery.sub(&montgomery.mul(&v, &v), &montgomery.add(&qk, &qk));
+        qk = montgomery.mul(&qk, &qk);
+        if d.bit(i) {
+            // U_2k+1 = (P U_2k + V_2k) / 2, V_2k+1 = (D U_2k + P V_2k) / 2
+            let u_next = half_mod(&montgomery.add(&u, &v), n);
+            v = half_mod(&montgomery.add(&montgomery.mul(&d_m, &u), &v), n);
+            u = u_next;
+            qk = montgomery.mul(&qk, &q_m);
+        }
+    }
+    if u == zero || v == zero {
+        return true;
+    }
+    for _ in 1..s {
+        v = montgomery.sub(&montgomery.mul(&v, &v), &montgomery.add(&qk, &qk));
+        if v == zero {
+            return true;
+        }
+        qk = montgomery.mul(&qk, &qk);
+    }
+    false
+}
+
+/// Jacobi symbol (a/n) for odd n
+pub fn jacobi(a: &IntegerAU, n: &IntegerAU) -> i32 {
+    assert!(n.bit(0), "Jacobi symbol requires an odd modulus");
+    let mut a = a.modulo(n).unwrap();
+    let mut n = n.clone();
+    let mut result = 1;
+    while !a.is_zero() {
+        let zeros = a.trailing_zeros().unwrap();
+        a = &a >> zeros;
+        // (2/n) = -1 when n is 3 or 5 mod 8
+        if zeros % 2 == 1 && matches!(n.limbs[0] & 7, 3 | 5) {
+            result = -result;
+        }
+        // quadratic reciprocity
+        std::mem::swap(&mut a, &mut n);
+        if a.limbs[0] & 3 == 3 && n.limbs[0] & 3 == 3 {
+            result = -result;
+        }
+        a = a.modulo(&n).unwrap();
+    }
+    if n == IntegerAU::from(1) {
+        result
+    } else {
+        0
+    }
+}
+
+// -v or v modulo n for a small v
+fn signed_mod(v: u64, negative: bool, n: &IntegerAU) -> IntegerAU {
+    let v = IntegerAU::from(v).modulo(n).unwrap();
+    if negative && !v.is_zero() {
+        n - &v
+    } else {
+        v
+    }
+}
+
+// a / 2 mod odd n
+fn half_mod(a: &IntegerAU, n: &IntegerAU) -> IntegerAU {
+    if a.bit(0) {
+        &(a + n) >> 1
+    } else {
+        a >> 1
+    }
+}
+
+/// Samples a random value with exactly `bits` bits, i.e. the top bit is set
+pub fn random_bits<R: Rng + ?Sized>(rng: &mut R, bits: usize) -> IntegerAU {
+    assert!(bits > 0, "bit length must be positive");
+    let mut limbs = (0..bits.div_ceil(64))
+        .map(|_| rng.gen())
+        .collect::<Vec<u64>>();
+    let top = (bits - 1) % 64;
+    let last = limbs.len() - 1;
+    limbs[last] &= u64::MAX >> (63 - top);
+    limbs[last] |= 1 << top;
+    IntegerAU { limbs }
+}
+
+/// Generates a random odd probable prime with exactly `bits` bits
+/// Panics if `bits` is less than 2.
+pub fn random_prime<R: Rng + ?Sized>(rng: &mut R, bits: usize) -> IntegerAU {
+    PrimeConstraints::new(bits).generate(rng).unwrap()
+}
+
+/// Shape of a prime to generate
+///
+/// ```ignore
+/// // a 64 bit prime with p - 1 divisible by 2^32, like goldilocks
+/// let constraints = PrimeConstraints {
+///     two_adicity: 32,
+///     ..PrimeConstraints::new(64)
+/// };
+/// let p = constraints.generate(&mut StdRng::seed_from_u64(1))?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrimeConstraints {
+    /// Exact bit length
+    pub bits: usize,
+    /// Minimum power of two dividing p - 1, i.e. p = k * 2^s + 1. Values
+    /// below 2 add nothing as every odd prime has 2-adicity at least 1
+    pub two_adicity: usize,
+    /// p = -1 mod 2^64 so that -p^-1 mod 2^64 is 1 and word by word
+    /// Montgomery reduction needs no multiplication to find the quotient
+    pub montgomery_friendly: bool,
+    /// (p - 1) / 2 is also prime
+    pub safe: bool,
+}
+
+// Fewer free bits than this are searched with a bounded number of attempts
+const SMALL_SEARCH_BITS: usize = 20;
+
+impl PrimeConstraints {
+    /// Constraints on the bit length only
+    pub fn new(bits: usize) -> Self {
+        Self {
+            bits,
+            two_adicity: 0,
+            montgomery_friendly: false,
+            safe: false,
+        }
+    }
+
+    /// Checks that primes of this shape can exist
+    pub fn validate(&self) -> Result<(), String> {
+        if self.bits < 2 {
+            return Err("no primes with fewer than 2 bits".to_string());
+        }
+        if self.two_adicity > 1 && (self.montgomery_friendly || self.safe) {
+            return Err(
+                "Montgomery-friendly and safe primes have 2-adicity 1, p = 3 mod 4".to_string(),
+            );
+        }
+        let forced = self.forced_low_bits();
+        if forced >= self.bits {
+            return Err(format!(
+                "{} bit primes can't have {forced} fixed low bits",
+                self.bits
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether p has this shape and is prime
+    pub fn is_satisfied_by(&self, p: &IntegerAU) -> bool {
+        let p_minus_one = p - &IntegerAU::from(1);
+        p.bit_len() == self.bits
+            && (self.two_adicity == 0
+                || p_minus_one.trailing_zeros().unwrap_or(0) >= self.two_adicity)
+            && (!self.montgomery_friendly || p.limbs[0] == u64::MAX)
+            && (!self.safe || baillie_psw(&(&p_minus_one >> 1)))
+            && baillie_psw(p)
+    }
+
+    /// Generates a random probable prime of this shape
+    /// Errors if the constraints are contradictory or, for shapes that fix
+    /// all but a few bits, no prime was found.
+    pub fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<IntegerAU, String> {
+        self.validate()?;
+        // the top and forced low bits are fixed
+        let free_bits = self.bits - 1 - self.forced_low_bits();
+        let max_attempts = if free_bits < SMALL_SEARCH_BITS {
+            16usize << free_bits
+        } else {
+            usize::MAX
+        };
+        for _ in 0..max_attempts {
+            let candidate = self.candidate(rng);
+            if self.safe {
+                // test q = (p - 1) / 2 cheaply before p
+                let q = &candidate >> 1;
+                if trivial_primality(&q) == Some(false)
+                    || trivial_primality(&candidate) == Some(false)
+                    || !miller_rabin(&q, MR_ROUNDS, rng)
+                {
+                    continue;
+                }
+            }
+            if miller_rabin(&candidate, MR_ROUNDS, rng) {
+                return Ok(candidate);
+            }
+        }
+        Err(format!("no prime found matching {self:?}"))
+    }
+
+    // Low bits fixed by the shape, the lowest bit is always set
+    fn forced_low_bits(&self) -> usize {
+        let mut forced = 1;
+        if self.two_adicity > 1 {
+            forced = self.two_adicity;
+        }
+        if self.safe {
+            forced = forced.max(2);
+        }
+        if self.montgomery_friendly {
+            forced = forced.max(64);
+        }
+        forced
+    }
+
+    // Random value with the top bit and the forced low bits set
+    fn candidate<R: Rng + ?Sized>(&self, rng: &mut R) -> IntegerAU {
+        let mut candidate = random_bits(rng, self.bits);
+        // p = 1 mod 2^s
+        for i in 1..self.two_adicity {
+            candidate.limbs[i / 64] &= !(1 << (i % 64));
+        }
+        candidate.limbs[0] |= 1;
+        if self.safe {
+            candidate.limbs[0] |= 3;
+        }
+        if self.montgomery_friendly {
+            candidate.limbs[0] = u64::MAX;
+        }
+        candidate
+    }
+}
+
+// n mod d for a single limb divisor
+fn rem_u64(n: &IntegerAU, d: u64) -> u64 {
+    n.limbs
+        .iter()
+        .rev()
+        .fold(0u128, |rem, &limb| ((rem << 64) | limb as u128) % d as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moduli::parse_modulus;
+    use crate::moduli::NAMED_MODULI;
+    use crate::seed::test_rng;
+    use crate::PRIMES;
+    use num_bigint::BigUint;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_miller_rabin_small() {
+        let mut rng = test_rng();
+        let sieve_limit = 2000u64;
+        let mut composite = vec![false; sieve_limit as usize];
+        for i in 2..sieve_limit as usize {
+            for j in (i * i..sieve_limit as usize).step_by(i) {
+                composite[j] = true;
+            }
+        }
+        for n in 0..sieve_limit {
+            let expected = n >= 2 && !composite[n as usize];
+            assert_eq!(
+                miller_rabin(&IntegerAU::from(n), 20, &mut rng),
+                expected,
+                "Failed primality test for {n}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_miller_rabin_large() {
+        let mut rng = test_rng();
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            assert!(miller_rabin(&p, 20, &mut rng), "{p} is prime");
+            // p * q for another prime q
+            let q = IntegerAU::from(1_000_000_007);
+            assert!(
+                !miller_rabin(&(&p * &q), 20, &mut rng),
+                "{p} * {q} is composite"
+            );
+        }
+    }
+
+    // 2047 .. 8321 are strong pseudoprimes to base 2, 3825123056546413051
+    // to every prime base up to 23, the rest are Carmichael numbers
+    const PSEUDOPRIMES: [u64; 12] = [
+        561,
+        1105,
+        1729,
+        2047,
+        3277,
+        4033,
+        4681,
+        8321,
+        41041,
+        825265,
+        3825123056546413051,
+        9999109081,
+    ];
+
+    #[test]
+    fn test_pseudoprimes() {
+        for n in PSEUDOPRIMES {
+            let n = IntegerAU::from(n);
+            assert!(
+                !n.is_probable_prime(PrimalityTest::BailliePsw),
+                "{n} is composite"
+            );
+            assert!(
+                !n.is_probable_prime(PrimalityTest::MillerRabin(20)),
+                "{n} is composite"
+            );
+        }
+        // (6k + 1)(12k + 1)(18k + 1) with all three factors prime is a
+        // Carmichael number, here for k = 2^100 + 8580
+        let k = &(&IntegerAU::from(1) << 100) + &IntegerAU::from(8580);
+        let factor = |m: u64| &(&k * &IntegerAU::from(m)) + &IntegerAU::from(1);
+        let chernick = &(&factor(6) * &factor(12)) * &factor(18);
+        assert!(!chernick.is_probable_prime(PrimalityTest::BailliePsw));
+        assert!(!chernick.is_probable_prime(PrimalityTest::MillerRabin(20)));
+        for m in [6, 12, 18] {
+            assert!(factor(m).is_probable_prime(PrimalityTest::BailliePsw));
+        }
+    }
+
+    #[test]
+    fn test_strong_lucas() {
+        // strong Lucas pseudoprimes with Selfridge parameters pass on their
+        // own but fail the base 2 test
+        for n in [
+            5459u64, 5777, 10877, 16109, 18971, 22499, 24569, 25199, 40309, 58519,
+        ] {
+            let n = IntegerAU::from(n);
+            assert!(
+                strong_lucas(&Montgomery::new(&n)),
+                "{n} is a strong Lucas pseudoprime"
+            );
+            assert!(!baillie_psw(&n), "{n} is composite");
+        }
+        // strong pseudoprimes to base 2 fail the Lucas test
+        for n in [2047u64, 3277, 4033, 4681, 8321] {
+            let n = IntegerAU::from(n);
+            assert!(!strong_lucas(&Montgomery::new(&n)), "{n} is composite");
+        }
+        // squares have no Selfridge parameter
+        assert!(!baillie_psw(&IntegerAU::from(1_000_003 * 1_000_003)));
+    }
+
+    #[test]
+    fn test_known_primes() {
+        let mut rng = test_rng();
+        for p_str in PRIMES.iter().chain(NAMED_MODULI.iter().map(|(_, v)| v)) {
+            let p = parse_modulus(p_str).unwrap();
+            assert!(
+                p.is_probable_prime(PrimalityTest::BailliePsw),
+                "{p} is prime"
+            );
+            assert!(miller_rabin(&p, 10, &mut rng), "{p} is prime");
+        }
+        // 2^4423 - 1, a Mersenne prime
+        let m4423 = &(&IntegerAU::from(1) << 4423) - &IntegerAU::from(1);
+        assert!(m4423.is_probable_prime(PrimalityTest::BailliePsw));
+    }
+
+    #[test]
+    fn test_jacobi() {
+        for n in (1u64..200).step_by(2) {
+            for a in 0..n {
+                let expected =
+                    BigUint::from(a).modpow(&BigUint::from((n - 1) / 2), &BigUint::from(n));
+                let j = jacobi(&IntegerAU::from(a), &IntegerAU::from(n));
+                // Euler's criterion, only valid for prime n
+                if baillie_psw(&IntegerAU::from(n)) {
+                    let euler = match j {
+                        0 => BigUint::from(0u64),
+                        1 => BigUint::from(1u64),
+                        _ => BigUint::from(n - 1),
+                    };
+                    assert_eq!(euler, expected, "({a}/{n})");
+                }
+            }
+        }
+        // (2/15) = (2/3)(2/5) = 1, (7/15) = (7/3)(7/5) = 1 * -1
+        assert_eq!(jacobi(&IntegerAU::from(2), &IntegerAU::from(15)), 1);
+        assert_eq!(jacobi(&IntegerAU::from(7), &IntegerAU::from(15)), -1);
+        assert_eq!(jacobi(&IntegerAU::from(6), &IntegerAU::from(15)), 0);
+    }
+
+    #[test]
+    fn test_constraints() {
+        let mut rng = StdRng::seed_from_u64(37);
+        let shapes = [
+            PrimeConstraints {
+                two_adicity: 32,
+                ..PrimeConstraints::new(64)
+            },
+            PrimeConstraints {
+                two_adicity: 100,
+                ..PrimeConstraints::new(128)
+            },
+            PrimeConstraints {
+                montgomery_friendly: true,
+                ..PrimeConstraints::new(127)
+            },
+            PrimeConstraints {
+                safe: true,
+                ..PrimeConstraints::new(64)
+            },
+            PrimeConstraints {
+                safe: true,
+                montgomery_friendly: true,
+                ..PrimeConstraints::new(80)
+            },
+            // few free bits, 2^7 * k + 1 for k in [2^3, 2^4)
+            PrimeConstraints {
+                two_adicity: 7,
+                ..PrimeConstraints::new(11)
+            },
+        ];
+        for shape in shapes {
+            let p = shape.generate(&mut rng).unwrap();
+            assert!(shape.is_satisfied_by(&p), "{p} does not match {shape:?}");
+        }
+    }
+
+    #[test]
+    fn test_constraints_reproducible() {
+        let shape = PrimeConstraints {
+            two_adicity: 16,
+            ..PrimeConstraints::new(96)
+        };
+        let a = shape.generate(&mut StdRng::seed_from_u64(1)).unwrap();
+        let b = shape.generate(&mut StdRng::seed_from_u64(1)).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_invalid_constraints() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let invalid = [
+            PrimeConstraints::new(1),
+            PrimeConstraints {
+                two_adicity: 9,
+                ..PrimeConstraints::new(9)
+            },
+            PrimeConstraints {
+                montgomery_friendly: true,
+                ..PrimeConstraints::new(64)
+            },
+            PrimeConstraints {
+                two_adicity: 4,
+                safe: true,
+                ..PrimeConstraints::new(64)
+            },
+        ];
+        for shape in invalid {
+            assert!(shape.generate(&mut rng).is_err(), "{shape:?} is invalid");
+        }
+        // 2^8 + 1 = 257 is prime, 2^9 + 1 = 3^3 * 19 is not
+        let fermat = PrimeConstraints {
+            two_adicity: 8,
+            ..PrimeConstraints::new(9)
+        };
+        assert_eq!(fermat.generate(&mut rng), Ok(IntegerAU::from(257)));
+        let none = PrimeConstraints {
+            two_adicity: 9,
+            ..PrimeConstraints::new(10)
+        };
+        assert!(none.generate(&mut rng).is_err());
+    }
+
+    #[test]
+    fn test_random_prime() {
+        let mut rng = test_rng();
+        for bits in [2, 3, 8, 63, 64, 65, 128, 255] {
+            let p = random_prime(&mut rng, bits);
+            assert_eq!(p.bit_len(), bits);
+            assert!(
+                num_bigint_is_prime(&p.to_biguint()),
+                "{p} with {bits} bits is not prime"
+            );
+        }
+    }
+
+    // Fermat tests to a few fixed bases as an independent check
+    fn num_bigint_is_prime(n: &BigUint) -> bool {
+        let one = BigUint::from(1u64);
+        let n_minus_one = n - &one;
+        [2u64, 3, 5, 7, 11, 13]
+            .into_iter()
+            .map(BigUint::from)
+            .filter(|a| a < n)
+            .all(|a| a.modpow(&n_minus_one, n) == one)
+    }
+}
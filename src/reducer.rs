@@ -0,0 +1,332 @@
+use super::integer_au::DecodeError;
+use super::integer_au::IntegerAU;
+use super::inversion;
+use super::pow;
+use super::sqrt;
+
+/// Reusable temporaries for the allocation-free `_into` reductions
+///
+/// The buffers grow to the size of the largest intermediate on first use,
+/// after which a loop of reductions sharing the scratch space doesn't
+/// allocate.
+#[derive(Debug, Clone)]
+pub struct Scratch {
+    pub(crate) product: IntegerAU,
+    pub(crate) a: IntegerAU,
+    pub(crate) b: IntegerAU,
+}
+
+impl Scratch {
+    pub fn new() -> Self {
+        Self {
+            product: IntegerAU::from(0),
+            a: IntegerAU::from(0),
+            b: IntegerAU::from(0),
+        }
+    }
+}
+
+impl Default for Scratch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Common interface over the modular reduction strategies
+///
+/// Values passed to and returned from the arithmetic methods are in the
+/// reducer's internal representation. For Barrett this is the canonical
+/// value in [0, p), for Montgomery it is the Montgomery form `x * R mod p`.
+pub trait Reducer {
+    /// The prime modulus this reducer operates over
+    fn modulus(&self) -> &IntegerAU;
+
+    /// Converts a value in [0, p) into the internal representation
+    fn to_internal(&self, v: &IntegerAU) -> IntegerAU;
+
+    /// Converts a value from the internal representation back into [0, p)
+    fn to_canonical(&self, v: &IntegerAU) -> IntegerAU;
+
+    /// Converts an owned value back into [0, p), reusing it when the
+    /// internal representation is already canonical
+    fn canonicalize(&self, v: IntegerAU) -> IntegerAU {
+        self.to_canonical(&v)
+    }
+
+    /// The multiplicative identity in the internal representation
+    fn one(&self) -> IntegerAU;
+
+    /// Multiplies two values in the internal representation
+    fn mul(&self, a: &IntegerAU, b: &IntegerAU) -> IntegerAU;
+
+    /// Squares a value in the internal representation
+    fn square(&self, a: &IntegerAU) -> IntegerAU {
+        self.mul(a, a)
+    }
+
+    /// Adds two values in the internal representation
+    fn add(&self, a: &IntegerAU, b: &IntegerAU) -> IntegerAU {
+        let width = self.modulus().limbs.len();
+        let (sum, carry) = a.overflowing_add(b, width);
+        // the sum is at least p if it carried out of the width or p can be
+        // subtracted without borrowing
+        let (reduced, borrow) = sum.overflowing_sub(self.modulus(), width);
+        if carry || !borrow {
+            reduced
+        } else {
+            sum
+        }
+    }
+
+    /// Subtracts two values in the internal representation
+    fn sub(&self, a: &IntegerAU, b: &IntegerAU) -> IntegerAU {
+        let width = self.modulus().limbs.len();
+        let (difference, borrow) = a.overflowing_sub(b, width);
+        if borrow {
+            difference.wrapping_add(self.modulus(), width)
+        } else {
+            difference
+        }
+    }
+
+    /// Multiplies two values in the internal representation into `out`,
+    /// keeping intermediates in `scratch`. Barrett and Montgomery don't
+    /// allocate once `out` and `scratch` have grown to size.
+    fn mul_into(&self, a: &IntegerAU, b: &IntegerAU, out: &mut IntegerAU, scratch: &mut Scratch) {
+        let _ = scratch;
+        *out = self.mul(a, b);
+    }
+
+    /// Computes base^exp using left-to-right binary exponentiation
+    fn pow(&self, base: &IntegerAU, exp: &IntegerAU) -> IntegerAU {
+        pow::pow_binary(self, base, exp)
+    }
+
+    /// Computes base^exp processing `window` bits of the exponent at a time
+    fn pow_fixed_window(&self, base: &IntegerAU, exp: &IntegerAU, window: usize) -> IntegerAU {
+        pow::pow_fixed_window(self, base, exp, window)
+    }
+
+    /// Computes base^exp using windows of at most `window` bits that
+    /// skip runs of zero bits
+    fn pow_sliding_window(&self, base: &IntegerAU, exp: &IntegerAU, window: usize) -> IntegerAU {
+        pow::pow_sliding_window(self, base, exp, window)
+    }
+
+    /// Computes the multiplicative inverse, None for zero
+    fn invert(&self, a: &IntegerAU) -> Option<IntegerAU> {
+        inversion::invert_fermat(self, a)
+    }
+
+    /// Computes the multiplicative inverse with the safegcd algorithm, None
+    /// for zero. The result is in the reducer's representation, Montgomery
+    /// form for Montgomery.
+    fn invert_safegcd(&self, a: &IntegerAU) -> Option<IntegerAU> {
+        inversion::invert_safegcd(&self.to_canonical(a), self.modulus())
+            .map(|inv| self.to_internal(&inv))
+    }
+
+    /// Computes the multiplicative inverse with Pornin's optimized binary
+    /// GCD, None for zero. Only supports moduli up to 256 bits.
+    fn invert_bingcd(&self, a: &IntegerAU) -> Option<IntegerAU> {
+        inversion::invert_bingcd(&self.to_canonical(a), self.modulus())
+            .map(|inv| self.to_internal(&inv))
+    }
+
+    /// Inverts all values in place with a single inversion, zeros are left
+    /// as zero
+    fn batch_invert(&self, values: &mut [IntegerAU]) {
+        inversion::batch_invert(self, values)
+    }
+
+    /// Legendre symbol of a value in the internal representation: 1 for
+    /// non-zero squares, -1 for non-squares and 0 for zero
+    fn legendre(&self, a: &IntegerAU) -> i32 {
+        sqrt::legendre(self, a)
+    }
+
+    /// Whether a value in the internal representation is a square, zero
+    /// included
+    fn is_square(&self, a: &IntegerAU) -> bool {
+        self.legendre(a) != -1
+    }
+
+    /// Square root of a value in the internal representation, None if it is
+    /// not a square. Uses the p = 3 mod 4 shortcut, Atkin's method for
+    /// p = 5 mod 8 and Tonelli-Shanks otherwise.
+    fn sqrt(&self, a: &IntegerAU) -> Option<IntegerAU> {
+        sqrt::sqrt(self, a)
+    }
+
+    /// Length in bytes of encoded field elements, the byte length of p
+    fn encoded_len(&self) -> usize {
+        self.modulus().byte_len()
+    }
+
+    /// Canonical little-endian encoding of a value in the internal
+    /// representation, `encoded_len` bytes long
+    fn encode_le(&self, v: &IntegerAU) -> Vec<u8> {
+        self.to_canonical(v)
+            .to_bytes_le_padded(self.encoded_len())
+            .expect("canonical values fit in the modulus length")
+    }
+
+    /// Canonical big-endian encoding of a value in the internal
+    /// representation, `encoded_len` bytes long
+    fn encode_be(&self, v: &IntegerAU) -> Vec<u8> {
+        let mut bytes = self.encode_le(v);
+        bytes.reverse();
+        bytes
+    }
+
+    /// Decodes a canonical little-endian encoding into the internal
+    /// representation, rejecting other lengths and values of at least p
+    fn decode_le(&self, bytes: &[u8]) -> Result<IntegerAU, DecodeError> {
+        if bytes.len() != self.encoded_len() {
+            return Err(DecodeError::Length {
+                expected: self.encoded_len(),
+                actual: bytes.len(),
+            });
+        }
+        let v = IntegerAU::from_bytes_le(bytes);
+        if &v >= self.modulus() {
+            return Err(DecodeError::NonCanonical);
+        }
+        Ok(self.to_internal(&v))
+    }
+
+    /// Decodes a canonical big-endian encoding into the internal
+    /// representation, rejecting other lengths and values of at least p
+    fn decode_be(&self, bytes: &[u8]) -> Result<IntegerAU, DecodeError> {
+        let mut bytes = bytes.to_vec();
+        bytes.reverse();
+        self.decode_le(&bytes)
+    }
+}
+
+/// Test inputs below p: the edge cases 0, 1 and p - 1 followed by `count`
+/// random values from `rng`
+#[cfg(test)]
+pub(crate) fn test_values<G: rand::Rng + ?Sized>(
+    p: &IntegerAU,
+    count: usize,
+    rng: &mut G,
+) -> Vec<IntegerAU> {
+    let mut values = vec![
+        IntegerAU::from(0),
+        IntegerAU::from(1),
+        p - &IntegerAU::from(1),
+    ];
+    values.extend((0..count).map(|_| IntegerAU::random_below_with(p, rng)));
+    values
+}
+
+/// Runs `check` with a Naive, Barrett and Montgomery reducer for every
+/// prime in `PRIMES`
+#[cfg(test)]
+pub(crate) fn for_each_reducer(check: impl FnMut(&dyn Reducer)) {
+    run_reducers(true, check)
+}
+
+/// Same as `for_each_reducer` without the Naive reducer, which is too slow
+/// for the large primes in debug builds
+#[cfg(test)]
+pub(crate) fn for_each_fast_reducer(check: impl FnMut(&dyn Reducer)) {
+    run_reducers(false, check)
+}
+
+#[cfg(test)]
+fn run_reducers(naive: bool, mut check: impl FnMut(&dyn Reducer)) {
+    use super::{barrett::Barrett, montgomery::Montgomery, naive::Naive, PRIMES};
+    use std::str::FromStr;
+
+    for p_str in PRIMES {
+        let p = IntegerAU::from_str(p_str).unwrap();
+        if naive {
+            check(&Naive::new(p.clone()));
+        }
+        check(&Barrett::new(p.clone()));
+        check(&Montgomery::new(&p));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::test_rng;
+
+    fn check_encoding(reducer: &dyn Reducer) {
+        let mut rng = test_rng();
+        let p = reducer.modulus();
+        let len = reducer.encoded_len();
+        for v in test_values(p, 50, &mut rng) {
+            let internal = reducer.to_internal(&v);
+            let le = reducer.encode_le(&internal);
+            let be = reducer.encode_be(&internal);
+            assert_eq!(le.len(), len);
+            assert_eq!(le, v.to_bytes_le_padded(len).unwrap());
+            assert_eq!(be, v.to_bytes_be_padded(len).unwrap());
+            assert_eq!(reducer.decode_le(&le), Ok(internal.clone()));
+            assert_eq!(reducer.decode_be(&be), Ok(internal));
+        }
+
+        for non_canonical in [p.clone(), p + &IntegerAU::from(1)] {
+            if let Some(bytes) = non_canonical.to_bytes_le_padded(len) {
+                assert_eq!(reducer.decode_le(&bytes), Err(DecodeError::NonCanonical));
+            }
+        }
+        assert_eq!(
+            reducer.decode_le(&vec![0; len + 1]),
+            Err(DecodeError::Length {
+                expected: len,
+                actual: len + 1
+            })
+        );
+        assert!(reducer.decode_be(&[]).is_err());
+        assert!(reducer.decode_be(&vec![0xff; len]).is_err());
+    }
+
+    fn check_add_sub(reducer: &dyn Reducer) {
+        let mut rng = test_rng();
+        let p = reducer.modulus();
+        let values = test_values(p, 10, &mut rng);
+        for a in &values {
+            for b in &values {
+                let (a_m, b_m) = (reducer.to_internal(a), reducer.to_internal(b));
+                let sum = reducer.to_canonical(&reducer.add(&a_m, &b_m));
+                let difference = reducer.to_canonical(&reducer.sub(&a_m, &b_m));
+                let (a_big, b_big, p_big) = (a.to_biguint(), b.to_biguint(), p.to_biguint());
+                assert_eq!(sum.to_biguint(), (&a_big + &b_big) % &p_big);
+                assert_eq!(difference.to_biguint(), (&a_big + &p_big - &b_big) % &p_big);
+            }
+        }
+    }
+
+    fn check_square(reducer: &dyn Reducer) {
+        let mut rng = test_rng();
+        let p = reducer.modulus();
+        for a in test_values(p, 10, &mut rng) {
+            let a_m = reducer.to_internal(&a);
+            let square = reducer.to_canonical(&reducer.square(&a_m));
+            assert_eq!(
+                square.to_biguint(),
+                a.to_biguint().modpow(&2u32.into(), &p.to_biguint())
+            );
+        }
+    }
+
+    #[test]
+    fn test_add_sub() {
+        for_each_reducer(check_add_sub);
+    }
+
+    #[test]
+    fn test_square() {
+        for_each_reducer(check_square);
+    }
+
+    #[test]
+    fn test_encoding() {
+        for_each_reducer(check_encoding);
+    }
+}
@@ -0,0 +1,762 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use super::op_count::OpCounts;
+use super::stats;
+use super::workload::Strategy;
+use super::workload::Workload;
+
+/// Timing statistics of one measurement, in nanoseconds
+/// `total_ns` covers every timed sample, the remaining fields are per
+/// operation, i.e. a sample divided by the iteration count. `min_ns` and
+/// `max_ns` are over all samples, the median, mean, standard deviation and
+/// confidence interval exclude outliers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub total_ns: f64,
+    pub median_ns: f64,
+    pub mean_ns: f64,
+    pub min_ns: f64,
+    pub max_ns: f64,
+    pub stddev_ns: f64,
+    /// Bounds of the 95% confidence interval of the median
+    pub ci_low_ns: f64,
+    pub ci_high_ns: f64,
+    /// Samples rejected by Tukey's fences
+    pub outliers: usize,
+}
+
+impl Stats {
+    /// Computes statistics over samples that each timed `iterations`
+    /// operations
+    pub fn from_samples(samples: &[Duration], iterations: usize) -> Self {
+        assert!(!samples.is_empty(), "no samples");
+        let per_op = |d: &Duration| d.as_nanos() as f64 / iterations.max(1) as f64;
+        let mut values = samples.iter().map(per_op).collect::<Vec<_>>();
+        values.sort_by(f64::total_cmp);
+        let (kept, outliers) = stats::reject_outliers(&values);
+        let (ci_low, ci_high) = stats::median_ci(kept).unwrap();
+        Self {
+            total_ns: samples.iter().map(|d| d.as_nanos() as f64).sum(),
+            median_ns: stats::median(kept).unwrap(),
+            mean_ns: stats::mean(kept).unwrap(),
+            min_ns: values[0],
+            max_ns: values[values.len() - 1],
+            stddev_ns: stats::stddev(kept),
+            ci_low_ns: ci_low,
+            ci_high_ns: ci_high,
+            outliers,
+        }
+    }
+}
+
+/// Machine the benchmarks ran on
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostInfo {
+    pub hostname: String,
+    pub os: String,
+    pub arch: String,
+    pub cpu: String,
+    pub cpus: usize,
+    /// "release" or "debug"
+    pub profile: String,
+    pub version: String,
+}
+
+impl HostInfo {
+    pub fn current() -> Self {
+        let hostname = std::fs::read_to_string("/etc/hostname")
+            .ok()
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .or_else(|| std::env::var("COMPUTERNAME").ok())
+            .map(|name| name.trim().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        // only linux exposes the cpu model without extra dependencies
+        let cpu = std::fs::read_to_string("/proc/cpuinfo")
+            .ok()
+            .and_then(|info| {
+                info.lines()
+                    .find(|line| line.starts_with("model name"))
+                    .and_then(|line| line.split_once(':'))
+                    .map(|(_, model)| model.trim().to_string())
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        Self {
+            hostname,
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            cpu,
+            cpus: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            profile: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            }
+            .to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Result of running one workload with one strategy over one modulus
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// Decimal representation of the modulus
+    pub modulus: String,
+    pub bits: usize,
+    pub strategy: Strategy,
+    pub workload: Workload,
+    pub iterations: usize,
+    pub samples: usize,
+    pub stats: Stats,
+    /// Work done by one run of `iterations` operations besides the products
+    /// being reduced, with the `op-count` feature
+    pub op_counts: Option<OpCounts>,
+}
+
+/// A set of records and the host that produced them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub host: HostInfo,
+    /// Seed the inputs were sampled with, None for reports written before
+    /// it was recorded
+    pub seed: Option<u64>,
+    pub records: Vec<Record>,
+}
+
+// Optional columns, empty without the op-count feature and absent from
+// reports written before it existed
+const OP_COUNT_HEADER: [&str; 5] = [
+    "limb_muls",
+    "limb_adds",
+    "allocations",
+    "integer_ops",
+    "reductions",
+];
+
+// Optional column repeated on every row like the host information, absent
+// from reports written before it existed
+const SEED_HEADER: &str = "seed";
+
+const CSV_HEADER: [&str; 22] = [
+    "modulus",
+    "bits",
+    "strategy",
+    "workload",
+    "iterations",
+    "samples",
+    "total_ns",
+    "median_ns_per_op",
+    "mean_ns_per_op",
+    "min_ns_per_op",
+    "max_ns_per_op",
+    "stddev_ns_per_op",
+    "ci_low_ns_per_op",
+    "ci_high_ns_per_op",
+    "outliers",
+    "hostname",
+    "os",
+    "arch",
+    "cpu",
+    "cpus",
+    "profile",
+    "version",
+];
+
+impl Report {
+    pub fn new(seed: u64, records: Vec<Record>) -> Self {
+        Self {
+            host: HostInfo::current(),
+            seed: Some(seed),
+            records,
+        }
+    }
+
+    /// Serializes the report as a JSON object with a `seed`, a `host` object
+    /// and a `records` array
+    pub fn to_json(&self) -> String {
+        let host = &self.host;
+        let mut out = String::new();
+        let seed = self
+            .seed
+            .map_or("null".to_string(), |seed| seed.to_string());
+        let _ = write!(out, "{{\n  \"seed\": {seed},\n  \"host\": {{\n");
+        let _ = writeln!(out, "    \"hostname\": {},", json_string(&host.hostname));
+        let _ = writeln!(out, "    \"os\": {},", json_string(&host.os));
+        let _ = writeln!(out, "    \"arch\": {},", json_string(&host.arch));
+        let _ = writeln!(out, "    \"cpu\": {},", json_string(&host.cpu));
+        let _ = writeln!(out, "    \"cpus\": {},", host.cpus);
+        let _ = writeln!(out, "    \"profile\": {},", json_string(&host.profile));
+        let _ = writeln!(out, "    \"version\": {}", json_string(&host.version));
+        out.push_str("  },\n  \"records\": [");
+        for (i, record) in self.records.iter().enumerate() {
+            let stats = &record.stats;
+            out.push_str(if i == 0 { "\n" } else { ",\n" });
+            let _ = write!(
+                out,
+                "    {{\"modulus\": {}, \"bits\": {}, \"strategy\": {}, \"workload\": {}, \
+                 \"iterations\": {}, \"samples\": {}, \"total_ns\": {}, \
+                 \"median_ns_per_op\": {}, \"mean_ns_per_op\": {}, \"min_ns_per_op\": {}, \
+                 \"max_ns_per_op\": {}, \"stddev_ns_per_op\": {}, \"ci_low_ns_per_op\": {}, \
+                 \"ci_high_ns_per_op\": {}, \"outliers\": {}, \"op_counts\": {}}}",
+                json_string(&record.modulus),
+                record.bits,
+                json_string(&record.strategy.to_string()),
+                json_string(&record.workload.to_string()),
+                record.iterations,
+                record.samples,
+                json_number(stats.total_ns),
+                json_number(stats.median_ns),
+                json_number(stats.mean_ns),
+                json_number(stats.min_ns),
+                json_number(stats.max_ns),
+                json_number(stats.stddev_ns),
+                json_number(stats.ci_low_ns),
+                json_number(stats.ci_high_ns),
+                stats.outliers,
+                match record.op_counts {
+                    Some(c) => format!(
+                        "{{\"limb_muls\": {}, \"limb_adds\": {}, \"allocations\": {}, \
+                         \"integer_ops\": {}, \"reductions\": {}}}",
+                        c.limb_muls, c.limb_adds, c.allocations, c.integer_ops, c.reductions
+                    ),
+                    None => "null".to_string(),
+                },
+            );
+        }
+        out.push_str(if self.records.is_empty() {
+            "]\n}\n"
+        } else {
+            "\n  ]\n}\n"
+        });
+        out
+    }
+
+    /// Serializes the report as CSV with a header row, host information and
+    /// the seed are repeated on every row
+    pub fn to_csv(&self) -> String {
+        let host = &self.host;
+        let mut out = CSV_HEADER.join(",");
+        out.push(',');
+        out.push_str(&OP_COUNT_HEADER.join(","));
+        out.push(',');
+        out.push_str(SEED_HEADER);
+        out.push('\n');
+        let seed = self.seed.map(|seed| seed.to_string()).unwrap_or_default();
+        for record in &self.records {
+            let stats = &record.stats;
+            let row = [
+                record.modulus.clone(),
+                record.bits.to_string(),
+                record.strategy.to_string(),
+                record.workload.to_string(),
+                record.iterations.to_string(),
+                record.samples.to_string(),
+                stats.total_ns.to_string(),
+                stats.median_ns.to_string(),
+                stats.mean_ns.to_string(),
+                stats.min_ns.to_string(),
+                stats.max_ns.to_string(),
+                stats.stddev_ns.to_string(),
+                stats.ci_low_ns.to_string(),
+                stats.ci_high_ns.to_string(),
+                stats.outliers.to_string(),
+                host.hostname.clone(),
+                host.os.clone(),
+                host.arch.clone(),
+                host.cpu.clone(),
+                host.cpus.to_string(),
+                host.profile.clone(),
+                host.version.clone(),
+            ];
+            let counts = match record.op_counts {
+                Some(c) => [
+                    c.limb_muls,
+                    c.limb_adds,
+                    c.allocations,
+                    c.integer_ops,
+                    c.reductions,
+                ]
+                .map(|v| v.to_string()),
+                None => Default::default(),
+            };
+            let row = row
+                .iter()
+                .chain(&counts)
+                .chain([&seed])
+                .map(|field| csv_field(field))
+                .collect::<Vec<_>>();
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parses a report written by `to_csv`
+    /// Host information and the seed are taken from the first row, columns
+    /// are matched by name so their order doesn't matter.
+    pub fn from_csv(csv: &str) -> Result<Self, String> {
+        let mut rows = parse_csv(csv)?.into_iter();
+        let header = rows.next().ok_or("empty report")?;
+        let column = |name: &str| {
+            header
+                .iter()
+                .position(|h| h == name)
+                .ok_or_else(|| format!("missing column \"{name}\""))
+        };
+        let columns = CSV_HEADER
+            .iter()
+            .map(|name| column(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let count_columns = OP_COUNT_HEADER.map(|name| header.iter().position(|h| h == name));
+        let seed_column = header.iter().position(|h| h == SEED_HEADER);
+
+        let mut host = None;
+        let mut seed = None;
+        let mut records = Vec::new();
+        for (line, row) in rows.enumerate() {
+            let field = |i: usize| -> &str { row.get(columns[i]).map_or("", String::as_str) };
+            let parse = |i: usize| -> Result<f64, String> {
+                field(i).parse::<f64>().map_err(|e| {
+                    format!(
+                        "row {}: invalid {} \"{}\": {e}",
+                        line + 1,
+                        CSV_HEADER[i],
+                        field(i)
+                    )
+                })
+            };
+            let parse_int = |i: usize| -> Result<usize, String> {
+                field(i).parse::<usize>().map_err(|e| {
+                    format!(
+                        "row {}: invalid {} \"{}\": {e}",
+                        line + 1,
+                        CSV_HEADER[i],
+                        field(i)
+                    )
+                })
+            };
+            if host.is_none() {
+                host = Some(HostInfo {
+                    hostname: field(15).to_string(),
+                    os: field(16).to_string(),
+                    arch: field(17).to_string(),
+                    cpu: field(18).to_string(),
+                    cpus: parse_int(19)?,
+                    profile: field(20).to_string(),
+                    version: field(21).to_string(),
+                });
+                seed = match seed_column.and_then(|c| row.get(c)) {
+                    Some(field) if !field.is_empty() => Some(field.parse().map_err(|e| {
+                        format!("row {}: invalid {SEED_HEADER} \"{field}\": {e}", line + 1)
+                    })?),
+                    _ => None,
+                };
+            }
+            records.push(Record {
+                modulus: field(0).to_string(),
+                bits: parse_int(1)?,
+                strategy: field(2).parse()?,
+                workload: field(3).parse()?,
+                iterations: parse_int(4)?,
+                samples: parse_int(5)?,
+                stats: Stats {
+                    total_ns: parse(6)?,
+                    median_ns: parse(7)?,
+                    mean_ns: parse(8)?,
+                    min_ns: parse(9)?,
+                    max_ns: parse(10)?,
+                    stddev_ns: parse(11)?,
+                    ci_low_ns: parse(12)?,
+                    ci_high_ns: parse(13)?,
+                    outliers: parse_int(14)?,
+                },
+                op_counts: parse_op_counts(&row, &count_columns)
+                    .map_err(|e| format!("row {}: {e}", line + 1))?,
+            });
+        }
+        Ok(Self {
+            host: host.unwrap_or_else(HostInfo::current),
+            seed,
+            records,
+        })
+    }
+
+    /// Renders one markdown table per workload with a row per bit length
+    /// and a column of median ns/op per strategy, for plotting how the
+    /// strategies scale with the modulus size
+    pub fn to_sweep_markdown(&self) -> String {
+        let mut out = self.markdown_seed();
+        for workload in Workload::ALL {
+            let records = self
+                .records
+                .iter()
+                .filter(|r| r.workload == workload)
+                .collect::<Vec<_>>();
+            if records.is_empty() {
+                continue;
+            }
+            let strategies = Strategy::ALL
+                .into_iter()
+                .filter(|s| records.iter().any(|r| r.strategy == *s))
+                .collect::<Vec<_>>();
+            let mut moduli = records
+                .iter()
+                .map(|r| (r.bits, r.modulus.as_str()))
+                .collect::<Vec<_>>();
+            moduli.sort();
+            moduli.dedup();
+
+            let _ = writeln!(out, "\n{workload} (median ns/op)\n");
+            out.push_str("| bits |");
+            for strategy in &strategies {
+                let _ = write!(out, " {} |", strategy.label());
+            }
+            out.push_str("\n|---|");
+            out.push_str(&"---|".repeat(strategies.len()));
+            out.push('\n');
+            for (bits, modulus) in moduli {
+                let _ = write!(out, "| {bits} |");
+                for strategy in &strategies {
+                    let record = records
+                        .iter()
+                        .find(|r| r.modulus == modulus && r.strategy == *strategy);
+                    match record {
+                        Some(r) => {
+                            let _ = write!(out, " {:.1} |", r.stats.median_ns);
+                        }
+                        None => out.push_str("  |"),
+                    }
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Renders the records as a markdown table
+    pub fn to_markdown(&self) -> String {
+        let mut out = self.markdown_seed();
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let counted = self.records.iter().any(|r| r.op_counts.is_some());
+        out.push_str(
+            "| modulus | bits | workload | reducer | iterations | median ns/op | 95% CI | outliers |",
+        );
+        if counted {
+            out.push_str(
+                " limb muls/reduction | limb adds/reduction | allocations/reduction \
+                 | integer ops/reduction |",
+            );
+        }
+        out.push_str("\n|---|---|---|---|---|---|---|---|");
+        if counted {
+            out.push_str("---|---|---|---|");
+        }
+        out.push('\n');
+        for record in &self.records {
+            let _ = write!(
+                out,
+                "| {} | {} | {} | {} | {} | {:.1} | {:.1}..{:.1} | {} |",
+                record.modulus,
+                record.bits,
+                record.workload,
+                record.strategy.label(),
+                record.iterations,
+                record.stats.median_ns,
+                record.stats.ci_low_ns,
+                record.stats.ci_high_ns,
+                record.stats.outliers
+            );
+            if counted {
+                match record.op_counts {
+                    Some(counts) => {
+                        for v in counts.per_reduction() {
+                            let _ = write!(out, " {v:.1} |");
+                        }
+                    }
+                    None => out.push_str(" | | | |"),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    // Line naming the seed that starts the markdown output
+    fn markdown_seed(&self) -> String {
+        self.seed
+            .map(|seed| format!("Inputs sampled with --seed {seed}\n"))
+            .unwrap_or_default()
+    }
+}
+
+// Quotes and escapes a string as a JSON string literal
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// JSON has no representation for NaN or infinity
+fn json_number(v: f64) -> String {
+    if v.is_finite() {
+        v.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+// Quotes a CSV field if it contains a separator, quote or newline (RFC 4180)
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// Operation counts of a csv row, None if the columns are missing or empty
+fn parse_op_counts(
+    row: &[String],
+    columns: &[Option<usize>; 5],
+) -> Result<Option<OpCounts>, String> {
+    let mut values = [0u64; 5];
+    for (i, column) in columns.iter().enumerate() {
+        let field = column.and_then(|c| row.get(c)).map_or("", String::as_str);
+        if field.is_empty() {
+            return Ok(None);
+        }
+        values[i] = field
+            .parse()
+            .map_err(|e| format!("invalid {} \"{field}\": {e}", OP_COUNT_HEADER[i]))?;
+    }
+    let [limb_muls, limb_adds, allocations, integer_ops, reductions] = values;
+    Ok(Some(OpCounts {
+        limb_muls,
+        limb_adds,
+        allocations,
+        integer_ops,
+        reductions,
+    }))
+}
+
+// Splits CSV text into rows of unquoted fields (RFC 4180), skipping empty
+// lines
+fn parse_csv(csv: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut chars = csv.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                if row.len() > 1 || !row[0].is_empty() {
+                    rows.push(std::mem::take(&mut row));
+                } else {
+                    row.clear();
+                }
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        let stats = Stats::from_samples(
+            &[
+                Duration::from_nanos(3000),
+                Duration::from_nanos(1000),
+                Duration::from_nanos(2000),
+            ],
+            10,
+        );
+        Report {
+            host: HostInfo {
+                hostname: "bench \"box\"".to_string(),
+                os: "linux".to_string(),
+                arch: "x86_64".to_string(),
+                cpu: "Some CPU, 8 cores".to_string(),
+                cpus: 8,
+                profile: "release".to_string(),
+                version: "0.1.0".to_string(),
+            },
+            seed: Some(42),
+            records: vec![Record {
+                modulus: "2013265921".to_string(),
+                bits: 31,
+                strategy: Strategy::Barrett,
+                workload: Workload::MulSum,
+                iterations: 10,
+                samples: 3,
+                stats,
+                op_counts: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = report().records[0].stats;
+        assert_eq!(stats.total_ns, 6000.0);
+        assert_eq!(stats.median_ns, 200.0);
+        assert_eq!(stats.min_ns, 100.0);
+        assert_eq!(stats.max_ns, 300.0);
+        assert_eq!(stats.stddev_ns, 100.0);
+        assert_eq!((stats.ci_low_ns, stats.ci_high_ns), (100.0, 300.0));
+        let single = Stats::from_samples(&[Duration::from_nanos(500)], 5);
+        assert_eq!(single.median_ns, 100.0);
+        assert_eq!(single.stddev_ns, 0.0);
+        // the outlier counts towards min and max but not the median
+        let samples = [10, 10, 11, 11, 12, 12, 100].map(Duration::from_nanos);
+        let stats = Stats::from_samples(&samples, 1);
+        assert_eq!(stats.outliers, 1);
+        assert_eq!(stats.median_ns, 11.0);
+        assert_eq!(stats.max_ns, 100.0);
+    }
+
+    #[test]
+    fn test_json() {
+        let json = report().to_json();
+        assert!(json.starts_with("{\n  \"seed\": 42,\n  \"host\": {\n"));
+        assert!(json.contains("\"hostname\": \"bench \\\"box\\\"\","));
+        assert!(json.contains(
+            "{\"modulus\": \"2013265921\", \"bits\": 31, \"strategy\": \"barrett\", \
+             \"workload\": \"mul-sum\", \"iterations\": 10, \"samples\": 3, \"total_ns\": 6000, \
+             \"median_ns_per_op\": 200, \"mean_ns_per_op\": 200, \"min_ns_per_op\": 100, \
+             \"max_ns_per_op\": 300, \"stddev_ns_per_op\": 100, \"ci_low_ns_per_op\": 100, \
+             \"ci_high_ns_per_op\": 300, \"outliers\": 0, \"op_counts\": null}"
+        ));
+        let empty = Report {
+            records: vec![],
+            ..report()
+        };
+        assert!(empty.to_json().ends_with("\"records\": []\n}\n"));
+    }
+
+    #[test]
+    fn test_sweep_markdown() {
+        let mut report = report();
+        let mut record = report.records[0].clone();
+        record.strategy = Strategy::Montgomery;
+        record.stats.median_ns = 150.0;
+        report.records.push(record);
+        assert_eq!(
+            report.to_sweep_markdown(),
+            "Inputs sampled with --seed 42\n\nmul-sum (median ns/op)\n\n| bits | Barrett | Montgomery |\n|---|---|---|\n\
+             | 31 | 200.0 | 150.0 |\n"
+        );
+        assert!(report
+            .to_markdown()
+            .starts_with("Inputs sampled with --seed 42\n\n| modulus | bits |"));
+    }
+
+    #[test]
+    fn test_csv_roundtrip() {
+        let original = report();
+        assert_eq!(Report::from_csv(&original.to_csv()), Ok(original));
+        assert!(Report::from_csv("").is_err());
+        assert!(Report::from_csv("modulus,bits\n3,2\n").is_err());
+        let bad = report().to_csv().replace(",barrett,", ",karatsuba,");
+        assert!(Report::from_csv(&bad).is_err());
+
+        let mut counted = report();
+        counted.records[0].op_counts = Some(OpCounts {
+            limb_muls: 40,
+            limb_adds: 60,
+            allocations: 30,
+            integer_ops: 50,
+            reductions: 10,
+        });
+        assert_eq!(Report::from_csv(&counted.to_csv()), Ok(counted));
+        // reports written before operation counting and seeds still parse
+        let csv = report().to_csv();
+        let old = csv
+            .lines()
+            .map(|line| line.rsplitn(7, ',').last().unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let unseeded = Report {
+            seed: None,
+            ..report()
+        };
+        assert_eq!(Report::from_csv(&old), Ok(unseeded.clone()));
+        assert_eq!(Report::from_csv(&unseeded.to_csv()), Ok(unseeded));
+    }
+
+    #[test]
+    fn test_op_counts() {
+        let mut report = report();
+        report.records[0].op_counts = Some(OpCounts {
+            limb_muls: 40,
+            limb_adds: 60,
+            allocations: 30,
+            integer_ops: 50,
+            reductions: 10,
+        });
+        assert!(report.to_json().contains(
+            "\"op_counts\": {\"limb_muls\": 40, \"limb_adds\": 60, \"allocations\": 30, \
+             \"integer_ops\": 50, \"reductions\": 10}}"
+        ));
+        assert!(report.to_csv().ends_with(",0.1.0,40,60,30,50,10,42\n"));
+        assert!(report
+            .to_markdown()
+            .ends_with("| 200.0 | 100.0..300.0 | 0 | 4.0 | 6.0 | 3.0 | 5.0 |\n"));
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = report().to_csv();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            [CSV_HEADER.as_slice(), &OP_COUNT_HEADER, &[SEED_HEADER]]
+                .concat()
+                .join(",")
+        );
+        assert_eq!(
+            lines[1],
+            "2013265921,31,barrett,mul-sum,10,3,6000,200,200,100,300,100,100,300,0,\
+             \"bench \"\"box\"\"\",linux,x86_64,\"Some CPU, 8 cores\",8,release,0.1.0,,,,,,42"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Seeds for reproducible benchmark inputs and tests
+//!
+//! A run takes its seed from `--seed`, else the `MODULI_SEED` environment
+//! variable, else a random one. The seed is reported so a run, or a failing
+//! test, can be replayed with the same inputs.
+
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Environment variable holding the seed of tests and benchmarks
+pub const SEED_ENV: &str = "MODULI_SEED";
+
+static SEED: OnceLock<u64> = OnceLock::new();
+
+/// Seed from `MODULI_SEED` or a random one if it's unset
+/// Panics if the variable is set to something other than a u64.
+pub fn seed_from_env() -> u64 {
+    match std::env::var(SEED_ENV) {
+        Ok(value) => value
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("{SEED_ENV} must be a u64, got \"{value}\"")),
+        Err(_) => rand::random(),
+    }
+}
+
+/// Seed shared by the whole process, taken from `seed_from_env` on first use
+pub fn global_seed() -> u64 {
+    *SEED.get_or_init(seed_from_env)
+}
+
+/// Deterministic RNG for a seed
+pub fn rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// Seed of an independent stream named by `label`, e.g. a benchmark and its
+/// arguments, so the values it sees don't depend on what else ran first
+pub fn derive(seed: u64, label: &str) -> u64 {
+    // FNV-1a, stable across platforms and compiler versions unlike the
+    // standard library hasher
+    label
+        .bytes()
+        .fold(seed ^ 0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+thread_local! {
+    static THREAD_RNG: RefCell<StdRng> = RefCell::new(rng(global_seed()));
+}
+
+/// Runs `f` with the calling thread's RNG, seeded with `global_seed`, for
+/// callers that don't take an RNG
+/// Panics if `f` uses the thread's RNG itself.
+pub fn with_thread_rng<T>(f: impl FnOnce(&mut StdRng) -> T) -> T {
+    THREAD_RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+/// RNG for a test seeded with `global_seed`, printing the seed if the test
+/// panics so the failure can be replayed with `MODULI_SEED`
+#[cfg(test)]
+pub(crate) struct TestRng {
+    rng: StdRng,
+    seed: u64,
+}
+
+#[cfg(test)]
+pub(crate) fn test_rng() -> TestRng {
+    let seed = global_seed();
+    TestRng {
+        rng: rng(seed),
+        seed,
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestRng {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            eprintln!("test failed with {SEED_ENV}={}", self.seed);
+        }
+    }
+}
+
+#[cfg(test)]
+impl rand::RngCore for TestRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integer_au::IntegerAU;
+    use rand::distributions::Distribution;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+
+    #[test]
+    fn test_same_seed_same_values() {
+        let upper = &IntegerAU::from(1) << 300;
+        let a = (0..10)
+            .map(|_| IntegerAU::random_below_with(&upper, &mut rng(7)))
+            .collect::<Vec<_>>();
+        let mut rng_b = rng(7);
+        let b = IntegerAU::random_below_with(&upper, &mut rng_b);
+        assert!(a.iter().all(|v| v == &b));
+        assert_ne!(b, IntegerAU::random_below_with(&upper, &mut rng_b));
+    }
+
+    #[test]
+    fn test_derive() {
+        assert_eq!(
+            derive(7, "bench_barrett(2013265921)"),
+            derive(7, "bench_barrett(2013265921)")
+        );
+        assert_ne!(
+            derive(7, "bench_barrett(2013265921)"),
+            derive(7, "bench_naive(2013265921)")
+        );
+        assert_ne!(
+            derive(7, "bench_barrett(2013265921)"),
+            derive(8, "bench_barrett(2013265921)")
+        );
+    }
+
+    #[test]
+    fn test_uniform_range() {
+        let mut rng = test_rng();
+        let low = IntegerAU::from(u64::MAX);
+        let high = &low + &IntegerAU::from(10);
+        let uniform = Uniform::new(&low, &high);
+        let mut seen = [false; 10];
+        for _ in 0..1000 {
+            let v = uniform.sample(&mut rng);
+            assert!(v >= low && v < high, "{v} out of range");
+            seen[(&v - &low).limbs[0] as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s), "not every value was sampled");
+        let v = rng.gen_range(low.clone()..=high.clone());
+        assert!(v >= low && v <= high);
+        assert_eq!(rng.gen_range(low.clone()..=low.clone()), low);
+    }
+}
@@ -0,0 +1,247 @@
+use super::integer_au::IntegerAU;
+use super::prime::jacobi;
+use super::reducer::Reducer;
+
+/// Legendre symbol of a value in the reducer's representation: 1 for
+/// non-zero squares, -1 for non-squares and 0 for zero
+pub fn legendre<R: Reducer + ?Sized>(reducer: &R, a: &IntegerAU) -> i32 {
+    let p = reducer.modulus();
+    if !p.bit(0) {
+        // every value is a square mod 2
+        return !a.is_zero() as i32;
+    }
+    jacobi(&reducer.to_canonical(a), p)
+}
+
+/// Square root of a value in the reducer's representation, None if it is
+/// not a square. The method is chosen from p mod 8: the p = 3 mod 4
+/// exponentiation, Atkin's for p = 5 mod 8 and Tonelli-Shanks otherwise.
+/// Which of the two roots is returned is unspecified.
+pub fn sqrt<R: Reducer + ?Sized>(reducer: &R, a: &IntegerAU) -> Option<IntegerAU> {
+    let p = reducer.modulus();
+    if !p.bit(0) || a.is_zero() {
+        return Some(a.clone());
+    }
+    match p.bits_at(0, 3) {
+        3 | 7 => sqrt_3_mod_4(reducer, a),
+        5 => sqrt_atkin(reducer, a),
+        _ => sqrt_tonelli_shanks(reducer, a),
+    }
+}
+
+/// Square root for p = 3 mod 4, r = a^((p + 1) / 4)
+/// One exponentiation and a squaring to reject non-squares.
+pub fn sqrt_3_mod_4<R: Reducer + ?Sized>(reducer: &R, a: &IntegerAU) -> Option<IntegerAU> {
+    let p = reducer.modulus();
+    assert_eq!(p.bits_at(0, 2), 3, "modulus must be 3 mod 4");
+    let r = reducer.pow(a, &(&(p + &IntegerAU::from(1)) >> 2));
+    (reducer.square(&r) == *a).then_some(r)
+}
+
+/// Atkin's square root for p = 5 mod 8 ("Probabilistic primality testing",
+/// 1992). With t = (2a)^((p - 5) / 8) and i = 2a t^2, a square root of -1,
+/// r = a t (i - 1). One exponentiation like the p = 3 mod 4 case.
+pub fn sqrt_atkin<R: Reducer + ?Sized>(reducer: &R, a: &IntegerAU) -> Option<IntegerAU> {
+    let p = reducer.modulus();
+    assert_eq!(p.bits_at(0, 3), 5, "modulus must be 5 mod 8");
+    let two_a = reducer.add(a, a);
+    let t = reducer.pow(&two_a, &(&(p - &IntegerAU::from(5)) >> 3));
+    let i = reducer.mul(&two_a, &reducer.square(&t));
+    let r = reducer.mul(&reducer.mul(a, &t), &reducer.sub(&i, &reducer.one()));
+    (reducer.square(&r) == *a).then_some(r)
+}
+
+/// Tonelli-Shanks square root for any odd prime
+/// With p - 1 = q 2^s and q odd, a^((q + 1) / 2) is corrected by powers of
+/// z^q for a non-square z until a^q is driven to one. Costs grow with s, so
+/// this is the method for high 2-adicity primes such as BabyBear and
+/// Goldilocks.
+pub fn sqrt_tonelli_shanks<R: Reducer + ?Sized>(reducer: &R, a: &IntegerAU) -> Option<IntegerAU> {
+    if a.is_zero() {
+        return Some(a.clone());
+    }
+    let p_minus_one = reducer.modulus() - &IntegerAU::from(1);
+    let s = p_minus_one.trailing_zeros().expect("modulus must be odd");
+    let q = &p_minus_one >> s;
+    let one = reducer.one();
+
+    let mut m = s;
+    let mut c = reducer.pow(&non_square(reducer), &q);
+    let mut t = reducer.pow(a, &q);
+    let mut x = reducer.pow(a, &(&(&q + &IntegerAU::from(1)) >> 1));
+    while t != one {
+        // least i with t^(2^i) = 1, a non-square never gets there
+        let mut i = 0;
+        let mut t_pow = t.clone();
+        while t_pow != one {
+            t_pow = reducer.square(&t_pow);
+            i += 1;
+            if i == m {
+                return None;
+            }
+        }
+        let mut b = c;
+        for _ in 0..m - i - 1 {
+            b = reducer.square(&b);
+        }
+        x = reducer.mul(&x, &b);
+        c = reducer.square(&b);
+        t = reducer.mul(&t, &c);
+        m = i;
+    }
+    Some(x)
+}
+
+/// Cipolla's square root for any odd prime
+/// Finds w with w^2 - a a non-square and computes (w + sqrt(w^2 - a))^((p + 1) / 2)
+/// in F_p^2. Costs do not depend on the 2-adicity of p.
+pub fn sqrt_cipolla<R: Reducer + ?Sized>(reducer: &R, a: &IntegerAU) -> Option<IntegerAU> {
+    if a.is_zero() {
+        return Some(a.clone());
+    }
+    if reducer.legendre(a) == -1 {
+        return None;
+    }
+    let (w, d) = (1u64..)
+        .map(|w| {
+            let w = reducer.to_internal(&IntegerAU::from(w));
+            let d = reducer.sub(&reducer.square(&w), a);
+            (w, d)
+        })
+        .find(|(_, d)| reducer.legendre(d) == -1)
+        .expect("half of the values are non-squares");
+
+    // (x1 + y1 u)(x2 + y2 u) with u^2 = d
+    let mul = |(x1, y1): &(IntegerAU, IntegerAU), (x2, y2): &(IntegerAU, IntegerAU)| {
+        (
+            reducer.add(&reducer.mul(x1, x2), &reducer.mul(&reducer.mul(y1, y2), &d)),
+            reducer.add(&reducer.mul(x1, y2), &reducer.mul(x2, y1)),
+        )
+    };
+    let base = (w, reducer.one());
+    let exp = &(reducer.modulus() + &IntegerAU::from(1)) >> 1;
+    let mut result = (reducer.one(), IntegerAU::from(0));
+    for bit in exp.bits().rev() {
+        result = mul(&result, &result);
+        if bit {
+            result = mul(&result, &base);
+        }
+    }
+    Some(result.0)
+}
+
+// Smallest non-square in the reducer's representation
+fn non_square<R: Reducer + ?Sized>(reducer: &R) -> IntegerAU {
+    (2u64..)
+        .map(|z| reducer.to_internal(&IntegerAU::from(z)))
+        .find(|z| reducer.legendre(z) == -1)
+        .expect("half of the values are non-squares")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::barrett::Barrett;
+    use crate::naive::Naive;
+    use crate::reducer::{for_each_fast_reducer, test_values};
+    use crate::seed::test_rng;
+    use num_bigint::BigUint;
+
+    type SqrtFn<R> = fn(&R, &IntegerAU) -> Option<IntegerAU>;
+
+    // Every method that applies to the modulus of `reducer`
+    fn methods<R: Reducer + ?Sized>(reducer: &R) -> Vec<(&'static str, SqrtFn<R>)> {
+        let mut methods: Vec<(&'static str, SqrtFn<R>)> = vec![
+            ("sqrt", sqrt),
+            ("tonelli_shanks", sqrt_tonelli_shanks),
+            ("cipolla", sqrt_cipolla),
+        ];
+        match reducer.modulus().bits_at(0, 3) {
+            3 | 7 => methods.push(("3_mod_4", sqrt_3_mod_4)),
+            5 => methods.push(("atkin", sqrt_atkin)),
+            _ => {}
+        }
+        methods
+    }
+
+    fn check_sqrt<R: Reducer + ?Sized>(reducer: &R) {
+        let mut rng = test_rng();
+        let p = reducer.modulus();
+        let p_big = p.to_biguint();
+        let euler_exp = (&p_big - 1u64) >> 1;
+        let mut values = test_values(p, 8, &mut rng);
+        // squares
+        values.extend((0..4).map(|_| {
+            IntegerAU::random_below_with(p, &mut rng)
+                .square()
+                .modulo(p)
+                .unwrap()
+        }));
+
+        for v in &values {
+            let a = reducer.to_internal(v);
+            // Euler's criterion
+            let expected = match v.to_biguint().modpow(&euler_exp, &p_big) {
+                e if e == BigUint::from(0u64) => 0,
+                e if e == BigUint::from(1u64) => 1,
+                _ => -1,
+            };
+            assert_eq!(
+                reducer.legendre(&a),
+                expected,
+                "Failed legendre test: ({v}/{p})"
+            );
+            assert_eq!(reducer.is_square(&a), expected != -1);
+
+            for (name, method) in methods(reducer) {
+                let root = method(reducer, &a);
+                match root {
+                    Some(r) => {
+                        assert_ne!(
+                            expected, -1,
+                            "{name} found a root of a non-square {v} mod {p}"
+                        );
+                        assert_eq!(
+                            reducer.square(&r),
+                            a,
+                            "Failed {name} test: sqrt({v}) mod {p}"
+                        );
+                    }
+                    None => assert_eq!(expected, -1, "{name} missed the root of {v} mod {p}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrt() {
+        for_each_fast_reducer(|reducer| check_sqrt(reducer));
+    }
+
+    #[test]
+    fn test_sqrt_small_primes() {
+        // 3 mod 4, 5 mod 8 and 1 mod 8 with 2-adicity 4
+        for p in [3u64, 19, 13, 29, 17, 97] {
+            // Naive reduction is too slow for the large primes in debug builds
+            check_sqrt(&Naive::new(IntegerAU::from(p)));
+            let barrett = Barrett::new(IntegerAU::from(p));
+            let squares = (0..p)
+                .filter(|&v| {
+                    let a = barrett.to_internal(&IntegerAU::from(v));
+                    let root = barrett.sqrt(&a);
+                    assert_eq!(root.is_some(), barrett.is_square(&a));
+                    root.is_some()
+                })
+                .count();
+            // zero and half of the non-zero values
+            assert_eq!(squares as u64, p.div_ceil(2), "modulus {p}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must be 5 mod 8")]
+    fn test_atkin_modulus() {
+        let barrett = Barrett::new(IntegerAU::from(17));
+        sqrt_atkin(&barrett, &IntegerAU::from(4));
+    }
+}
@@ -0,0 +1,118 @@
+//! Summary statistics for repeated timing samples.
+//!
+//! Samples are filtered with Tukey's fences before summarizing, timing
+//! outliers are almost always caused by the OS or other processes rather
+//! than the code being measured. The median is reported with a
+//! distribution-free confidence interval since timings are rarely normal.
+
+/// Multiple of the interquartile range beyond which a sample is an outlier
+pub const TUKEY_K: f64 = 1.5;
+/// z value of the two sided 95% confidence level
+pub const Z_95: f64 = 1.96;
+
+/// Median of sorted values, None if empty
+pub fn median(sorted: &[f64]) -> Option<f64> {
+    quantile(sorted, 0.5)
+}
+
+/// Quantile of sorted values by linear interpolation between closest ranks,
+/// None if empty
+pub fn quantile(sorted: &[f64], q: f64) -> Option<f64> {
+    let last = sorted.len().checked_sub(1)?;
+    let pos = q.clamp(0.0, 1.0) * last as f64;
+    let lower = pos.floor() as usize;
+    let upper = pos.ceil() as usize;
+    Some(sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f64))
+}
+
+/// Splits sorted values into those inside Tukey's fences and the number of
+/// outliers outside them
+pub fn reject_outliers(sorted: &[f64]) -> (&[f64], usize) {
+    // quartiles of fewer than 4 samples say nothing about outliers
+    if sorted.len() < 4 {
+        return (sorted, 0);
+    }
+    let q1 = quantile(sorted, 0.25).unwrap();
+    let q3 = quantile(sorted, 0.75).unwrap();
+    let low = q1 - TUKEY_K * (q3 - q1);
+    let high = q3 + TUKEY_K * (q3 - q1);
+    let start = sorted.partition_point(|&v| v < low);
+    let end = sorted.partition_point(|&v| v <= high);
+    (&sorted[start..end], sorted.len() - (end - start))
+}
+
+/// Approximate 95% confidence interval of the median of sorted values
+/// Uses the order statistics at ranks n/2 -/+ 1.96 sqrt(n) / 2, which holds
+/// for any distribution. Returns (min, max) when there are too few samples
+/// for the interval to be narrower.
+pub fn median_ci(sorted: &[f64]) -> Option<(f64, f64)> {
+    let n = sorted.len();
+    let last = n.checked_sub(1)?;
+    let half_width = Z_95 * (n as f64).sqrt() / 2.0;
+    let center = n as f64 / 2.0;
+    let lower = (center - half_width).floor().max(0.0) as usize;
+    let upper = ((center + half_width).ceil() as usize).min(last);
+    Some((sorted[lower], sorted[upper]))
+}
+
+/// Arithmetic mean, None if empty
+pub fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Sample standard deviation, zero for fewer than 2 values
+pub fn stddev(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let mean = mean(values).unwrap();
+    let sum_sq = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>();
+    (sum_sq / (values.len() - 1) as f64).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantiles() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[3.0]), Some(3.0));
+        assert_eq!(median(&[1.0, 2.0, 3.0]), Some(2.0));
+        assert_eq!(median(&[1.0, 2.0, 3.0, 4.0]), Some(2.5));
+        assert_eq!(quantile(&[1.0, 2.0, 3.0, 4.0, 5.0], 0.25), Some(2.0));
+        assert_eq!(quantile(&[1.0, 2.0, 3.0, 4.0, 5.0], 1.0), Some(5.0));
+    }
+
+    #[test]
+    fn test_reject_outliers() {
+        let sorted = [1.0, 10.0, 10.0, 11.0, 11.0, 12.0, 12.0, 100.0];
+        let (kept, outliers) = reject_outliers(&sorted);
+        assert_eq!(kept, &sorted[1..7]);
+        assert_eq!(outliers, 2);
+        // too few samples to judge
+        assert_eq!(reject_outliers(&[1.0, 100.0]), (&[1.0, 100.0][..], 0));
+        // identical samples are all kept
+        assert_eq!(reject_outliers(&[5.0; 6]).1, 0);
+    }
+
+    #[test]
+    fn test_median_ci() {
+        assert_eq!(median_ci(&[]), None);
+        assert_eq!(median_ci(&[1.0, 2.0, 3.0]), Some((1.0, 3.0)));
+        let sorted = (0..100).map(f64::from).collect::<Vec<_>>();
+        // ranks 50 -/+ 9.8
+        assert_eq!(median_ci(&sorted), Some((40.0, 60.0)));
+    }
+
+    #[test]
+    fn test_mean_stddev() {
+        assert_eq!(mean(&[]), None);
+        assert_eq!(mean(&[1.0, 2.0, 3.0]), Some(2.0));
+        assert_eq!(stddev(&[1.0, 2.0, 3.0]), 1.0);
+        assert_eq!(stddev(&[4.0]), 0.0);
+    }
+}
@@ -0,0 +1,527 @@
+use std::fmt;
+use std::hint::black_box;
+use std::str::FromStr;
+use std::time::Duration;
+use std::time::Instant;
+
+use num_bigint::BigUint;
+use rand::Rng;
+
+use super::barrett::Barrett;
+use super::integer_au::IntegerAU;
+use super::montgomery::Montgomery;
+use super::naive::Naive;
+use super::op_count;
+use super::op_count::OpCounts;
+use super::prime::baillie_psw;
+use super::reducer::Reducer;
+
+/// Rounds in the Poseidon approximation, every round is a full round
+pub const POSEIDON_ROUNDS: usize = 70;
+/// State elements in the Poseidon approximation, as in PoseidonT3
+pub const POSEIDON_WIDTH: usize = 3;
+
+/// How products are reduced modulo p
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Naive,
+    Barrett,
+    Montgomery,
+    BigUint,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 4] = [
+        Strategy::Naive,
+        Strategy::Barrett,
+        Strategy::Montgomery,
+        Strategy::BigUint,
+    ];
+
+    /// Human readable name used in reports
+    pub fn label(&self) -> &'static str {
+        match self {
+            Strategy::Naive => "Naïve",
+            Strategy::Barrett => "Barrett",
+            Strategy::Montgomery => "Montgomery",
+            Strategy::BigUint => "BigUint",
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Strategy::Naive => "naive",
+            Strategy::Barrett => "barrett",
+            Strategy::Montgomery => "montgomery",
+            Strategy::BigUint => "biguint",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Strategy::ALL
+            .into_iter()
+            .find(|strategy| strategy.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown reducer \"{s}\""))
+    }
+}
+
+/// The operation sequence being timed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Workload {
+    /// Independent multiplications of random pairs
+    Mul,
+    /// Multiplications of random pairs followed by a sum of the products
+    MulSum,
+    /// Exponentiations of random bases by random exponents below p
+    Pow,
+    /// Poseidon-like permutations using x^5 s-boxes
+    Poseidon,
+    /// Radix 2 number theoretic transforms
+    Ntt,
+}
+
+impl Workload {
+    pub const ALL: [Workload; 5] = [
+        Workload::Mul,
+        Workload::MulSum,
+        Workload::Pow,
+        Workload::Poseidon,
+        Workload::Ntt,
+    ];
+
+    pub fn default_iterations(&self) -> usize {
+        match self {
+            Workload::Mul => 1000,
+            Workload::MulSum => 10000,
+            Workload::Pow => 100,
+            Workload::Poseidon => 10,
+            Workload::Ntt => 10,
+        }
+    }
+
+    /// Describes `iterations` runs of the workload, e.g. "1000 multiplications"
+    pub fn describe(&self, iterations: usize) -> String {
+        match self {
+            Workload::Mul => format!("{iterations} multiplications"),
+            Workload::MulSum => format!("{iterations} multiplications and summation"),
+            Workload::Pow => format!("{iterations} exponentiations"),
+            Workload::Poseidon => format!("{iterations} permutations"),
+            Workload::Ntt => format!("{iterations} transforms"),
+        }
+    }
+}
+
+impl fmt::Display for Workload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Workload::Mul => "mul",
+            Workload::MulSum => "mul-sum",
+            Workload::Pow => "pow",
+            Workload::Poseidon => "poseidon",
+            Workload::Ntt => "ntt",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Workload {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Workload::ALL
+            .into_iter()
+            .find(|workload| workload.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown workload \"{s}\""))
+    }
+}
+
+/// Random inputs for one workload over one modulus
+/// The same inputs are given to every strategy so their outputs can be
+/// compared.
+pub struct Inputs {
+    pub workload: Workload,
+    pub prime: IntegerAU,
+    pub iterations: usize,
+    // canonical values in [0, p), what they are depends on the workload
+    values: Vec<IntegerAU>,
+    exponents: Vec<IntegerAU>,
+    root_of_unity: Option<IntegerAU>,
+}
+
+impl Inputs {
+    /// Samples inputs for `iterations` runs of the workload from `rng`
+    /// Returns None if the workload can't run over this modulus, which
+    /// happens for the NTT when p is not prime or 2^ntt_log_size does not
+    /// divide p - 1.
+    pub fn sample<R: Rng + ?Sized>(
+        workload: Workload,
+        prime: &IntegerAU,
+        iterations: usize,
+        ntt_log_size: u32,
+        rng: &mut R,
+    ) -> Option<Self> {
+        let mut random = |count: usize| {
+            (0..count)
+                .map(|_| IntegerAU::random_below_with(prime, rng))
+                .collect::<Vec<_>>()
+        };
+        let mut exponents = Vec::new();
+        let mut root_of_unity = None;
+        let values = match workload {
+            Workload::Mul | Workload::MulSum => random(2 * iterations),
+            Workload::Pow => {
+                exponents = random(iterations);
+                random(iterations)
+            }
+            Workload::Poseidon => random(POSEIDON_ROUNDS * POSEIDON_WIDTH),
+            Workload::Ntt => {
+                root_of_unity = Some(root_of_unity_of_order(prime, ntt_log_size)?);
+                random(iterations << ntt_log_size)
+            }
+        };
+        Some(Self {
+            workload,
+            prime: prime.clone(),
+            iterations,
+            values,
+            exponents,
+            root_of_unity,
+        })
+    }
+}
+
+/// Time taken by each timed run and the canonical values produced by the
+/// last one
+pub struct Measurement {
+    pub samples: Vec<Duration>,
+    pub output: Vec<IntegerAU>,
+    /// Work done by one run, with the `op-count` feature
+    pub counts: Option<OpCounts>,
+}
+
+impl Measurement {
+    /// Sum of all timed runs
+    pub fn elapsed(&self) -> Duration {
+        self.samples.iter().sum()
+    }
+}
+
+/// Runs the workload `warmup` times without timing it and then `samples`
+/// times timed, at least once
+/// Returns None if the strategy does not implement the workload.
+pub fn run(
+    strategy: Strategy,
+    inputs: &Inputs,
+    warmup: usize,
+    samples: usize,
+) -> Option<Measurement> {
+    let p = inputs.prime.clone();
+    match strategy {
+        Strategy::Naive => Some(run_reducer(&Naive::new(p), inputs, warmup, samples)),
+        Strategy::Barrett => Some(run_reducer(&Barrett::new(p), inputs, warmup, samples)),
+        Strategy::Montgomery => Some(run_reducer(&Montgomery::new(&p), inputs, warmup, samples)),
+        Strategy::BigUint => run_biguint(inputs, warmup, samples),
+    }
+}
+
+// Runs `f` untimed `warmup` times then timed `samples` times, and once more
+// to count operations if enabled
+fn measure<T>(
+    warmup: usize,
+    samples: usize,
+    f: impl Fn() -> T,
+) -> (Vec<Duration>, T, Option<OpCounts>) {
+    for _ in 0..warmup {
+        black_box(f());
+    }
+    let mut durations = Vec::with_capacity(samples.max(1));
+    loop {
+        let start = Instant::now();
+        // keep the compiler from discarding or hoisting the work
+        let output = black_box(f());
+        durations.push(start.elapsed());
+        if durations.len() >= samples {
+            let counts = op_count::ENABLED.then(|| op_count::count(&f).1);
+            return (durations, output, counts);
+        }
+        black_box(output);
+    }
+}
+
+// Inputs are converted into the reducer's representation before timing and
+// outputs are converted back while timing
+fn run_reducer<R: Reducer>(
+    reducer: &R,
+    inputs: &Inputs,
+    warmup: usize,
+    samples: usize,
+) -> Measurement {
+    let values = inputs
+        .values
+        .iter()
+        .map(|v| reducer.to_internal(v))
+        .collect::<Vec<_>>();
+    let ntt_size = inputs.values.len() / inputs.iterations.max(1);
+    let twiddles = inputs.root_of_unity.as_ref().map(|w| {
+        let half = ntt_size / 2;
+        let w = reducer.to_internal(w);
+        let mut twiddles = vec![reducer.one()];
+        for i in 1..half {
+            twiddles.push(reducer.mul(&twiddles[i - 1], &w));
+        }
+        twiddles
+    });
+
+    let run_once = || -> Vec<IntegerAU> {
+        let values = black_box(&values);
+        match inputs.workload {
+            Workload::Mul => values
+                .chunks(2)
+                .map(|pair| reducer.canonicalize(reducer.mul(&pair[0], &pair[1])))
+                .collect(),
+            Workload::MulSum => {
+                let products = values
+                    .chunks(2)
+                    .map(|pair| reducer.mul(&pair[0], &pair[1]))
+                    .collect::<Vec<_>>();
+                let sum = products
+                    .iter()
+                    .fold(IntegerAU::from(0), |acc, x| reducer.add(&acc, x));
+                vec![reducer.canonicalize(sum)]
+            }
+            Workload::Pow => values
+                .iter()
+                .zip(&inputs.exponents)
+                .map(|(base, exp)| reducer.canonicalize(reducer.pow(base, exp)))
+                .collect(),
+            Workload::Poseidon => {
+                let mut state = vec![IntegerAU::from(0); POSEIDON_WIDTH];
+                for _ in 0..inputs.iterations {
+                    poseidon_permutation(reducer, &mut state, values);
+                }
+                state.into_iter().map(|v| reducer.canonicalize(v)).collect()
+            }
+            Workload::Ntt => {
+                let twiddles = twiddles.as_ref().unwrap();
+                let mut output = Vec::with_capacity(values.len());
+                for chunk in values.chunks(ntt_size.max(1)) {
+                    let mut a = chunk.to_vec();
+                    ntt(reducer, &mut a, twiddles);
+                    output.extend(a.into_iter().map(|v| reducer.canonicalize(v)));
+                }
+                output
+            }
+        }
+    };
+
+    let (samples, output, counts) = measure(warmup, samples, run_once);
+    Measurement {
+        samples,
+        output,
+        counts,
+    }
+}
+
+fn run_biguint(inputs: &Inputs, warmup: usize, samples: usize) -> Option<Measurement> {
+    let p = inputs.prime.to_biguint();
+    let values = inputs
+        .values
+        .iter()
+        .map(|v| v.to_biguint())
+        .collect::<Vec<_>>();
+    let exponents = inputs
+        .exponents
+        .iter()
+        .map(|v| v.to_biguint())
+        .collect::<Vec<_>>();
+
+    let run_once = || -> Vec<BigUint> {
+        let values = black_box(&values);
+        match inputs.workload {
+            Workload::Mul => values
+                .chunks(2)
+                .map(|pair| (&pair[0] * &pair[1]) % &p)
+                .collect(),
+            Workload::MulSum => {
+                let products = values
+                    .chunks(2)
+                    .map(|pair| (&pair[0] * &pair[1]) % &p)
+                    .collect::<Vec<_>>();
+                let sum = products
+                    .iter()
+                    .fold(BigUint::from(0u64), |acc, x| (&acc + x) % &p);
+                vec![sum]
+            }
+            Workload::Pow => values
+                .iter()
+                .zip(&exponents)
+                .map(|(base, exp)| base.modpow(exp, &p))
+                .collect(),
+            Workload::Poseidon | Workload::Ntt => unreachable!(),
+        }
+    };
+    if matches!(inputs.workload, Workload::Poseidon | Workload::Ntt) {
+        return None;
+    }
+
+    // BigUint arithmetic isn't instrumented
+    let (samples, output, _) = measure(warmup, samples, run_once);
+    Some(Measurement {
+        samples,
+        counts: None,
+        output: output.into_iter().map(IntegerAU::from_biguint).collect(),
+    })
+}
+
+// One permutation of the PoseidonT3 approximation used by the divan
+// benchmarks: add round constants, x^5 s-box and three squarings standing in
+// for the linear layer
+fn poseidon_permutation<R: Reducer>(
+    reducer: &R,
+    state: &mut [IntegerAU],
+    round_constants: &[IntegerAU],
+) {
+    let pow5 = |x: &IntegerAU| {
+        let x2 = reducer.mul(x, x);
+        let x4 = reducer.mul(&x2, &x2);
+        reducer.mul(&x4, x)
+    };
+    for constants in round_constants.chunks(state.len()) {
+        for (s, c) in state.iter_mut().zip(constants) {
+            *s = pow5(&reducer.add(s, c));
+            for _ in 0..3 {
+                *s = reducer.mul(s, s);
+            }
+        }
+    }
+}
+
+// In place iterative radix 2 decimation in time transform
+// twiddles[i] is w^i for a primitive root of unity w of order a.len()
+fn ntt<R: Reducer>(reducer: &R, a: &mut [IntegerAU], twiddles: &[IntegerAU]) {
+    let n = a.len();
+    if n <= 1 {
+        return;
+    }
+    let log_n = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let stride = n / len;
+        for start in (0..n).step_by(len) {
+            for j in 0..half {
+                let v = reducer.mul(&a[start + j + half], &twiddles[j * stride]);
+                let u = a[start + j].clone();
+                a[start + j + half] = reducer.sub(&u, &v);
+                a[start + j] = reducer.add(&u, &v);
+            }
+        }
+        len <<= 1;
+    }
+}
+
+// Finds a primitive root of unity of order 2^log_size, None if p is not
+// prime or p - 1 is not divisible by 2^log_size. The search over candidate
+// generators only terminates for a prime, where half of them are non-squares.
+fn root_of_unity_of_order(p: &IntegerAU, log_size: u32) -> Option<IntegerAU> {
+    if !baillie_psw(p) {
+        return None;
+    }
+    let p_minus_one = p - &IntegerAU::from(1);
+    let two_adicity = p_minus_one.trailing_zeros().unwrap_or(0);
+    if log_size as usize > two_adicity {
+        return None;
+    }
+    let barrett = Barrett::new(p.clone());
+    let cofactor = &p_minus_one >> log_size as usize;
+    let one = IntegerAU::from(1);
+    (2u64..).map(IntegerAU::from).find_map(|g| {
+        let w = barrett.pow(&g, &cofactor);
+        // w has order exactly 2^log_size if w^(2^(log_size - 1)) != 1
+        let mut check = w.clone();
+        for _ in 1..log_size {
+            check = barrett.mul(&check, &check);
+        }
+        (log_size == 0 || check != one).then_some(w)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::test_rng;
+    use crate::PRIMES;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_strategies_agree() {
+        let mut rng = test_rng();
+        for p_str in PRIMES {
+            let p = IntegerAU::from_str(p_str).unwrap();
+            for workload in Workload::ALL {
+                let Some(inputs) = Inputs::sample(workload, &p, 3, 4, &mut rng) else {
+                    continue;
+                };
+                let outputs = Strategy::ALL
+                    .into_iter()
+                    .filter_map(|strategy| Some((strategy, run(strategy, &inputs, 0, 1)?)))
+                    .collect::<Vec<_>>();
+                for (strategy, measurement) in &outputs[1..] {
+                    assert_eq!(
+                        measurement.output, outputs[0].1.output,
+                        "{} {} output mismatches naive reduction mod {}",
+                        strategy, workload, p
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ntt_matches_naive_dft() {
+        let p = IntegerAU::from_str(PRIMES[0]).unwrap();
+        let inputs = Inputs::sample(Workload::Ntt, &p, 1, 3, &mut test_rng()).unwrap();
+        let output = run(Strategy::Barrett, &inputs, 0, 1).unwrap().output;
+        let barrett = Barrett::new(p.clone());
+        let w = inputs.root_of_unity.unwrap();
+        for (k, out) in output.iter().enumerate() {
+            let expected =
+                inputs
+                    .values
+                    .iter()
+                    .enumerate()
+                    .fold(IntegerAU::from(0), |acc, (j, x)| {
+                        let twiddle = barrett.pow(&w, &IntegerAU::from((j * k) as u64));
+                        barrett.add(&acc, &barrett.mul(x, &twiddle))
+                    });
+            assert_eq!(out, &expected, "Failed NTT test at index {k}");
+        }
+    }
+
+    #[test]
+    fn test_root_of_unity() {
+        // babybear has two-adicity 27, goldilocks 32, 2^127 - 1 only 1
+        let babybear = IntegerAU::from_str(PRIMES[0]).unwrap();
+        let m127 = IntegerAU::from_str(PRIMES[2]).unwrap();
+        assert!(root_of_unity_of_order(&babybear, 27).is_some());
+        assert!(root_of_unity_of_order(&babybear, 28).is_none());
+        assert!(root_of_unity_of_order(&m127, 1).is_some());
+        assert!(root_of_unity_of_order(&m127, 2).is_none());
+        // 2^32 + 1 = 641 * 6700417 has 2-adicity 32 but no such root
+        let fermat5 = IntegerAU::from((1u64 << 32) + 1);
+        assert!(root_of_unity_of_order(&fermat5, 4).is_none());
+    }
+}